@@ -1,6 +1,23 @@
+use advent_of_code::parse::Span;
+use advent_of_code::template::validate::Validator;
 use itertools::{sorted, Itertools};
 
-advent_of_code::solution!(1);
+advent_of_code::solution!(1, validate = validate);
+
+/// Every line holds two location IDs, one of each list.
+fn validate(input: &mut Validator) {
+    for line in input.input().lines() {
+        let Some(ids) = input.check(line.words(Span::unsigned::<usize>)) else {
+            continue;
+        };
+        if ids.len() != 2 {
+            input.error(
+                line,
+                format!("expected 2 location IDs, found {}", ids.len()),
+            );
+        }
+    }
+}
 
 fn parse(input: &str) -> Vec<(usize, usize)> {
     input.lines().map(parse_line).collect()
@@ -52,4 +69,18 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(31));
     }
+
+    #[test]
+    fn test_validate() {
+        let mut validator = Validator::new("3   4\n4 -3\n9\n");
+        validate(&mut validator);
+        let messages: Vec<String> = validator.finish().iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "error: line 2, column 3: expected unsigned integer, found '-3'",
+                "error: line 3, column 1: expected 2 location IDs, found 1",
+            ]
+        );
+    }
 }
@@ -87,6 +87,54 @@ pub fn part_two(input: &str) -> Option<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::property::{differential, Gen};
+
+    /// Index of the first level whose step to the next one is unsafe, given the direction of
+    /// the first step.
+    fn first_unsafe_step(row: &[i64]) -> Option<usize> {
+        let direction = row.get(1).map(|next| (next - row[0]).signum());
+        row.iter().tuple_windows().position(|(a, b)| {
+            let delta = b - a;
+            delta == 0 || delta.abs() > 3 || Some(delta.signum()) != direction
+        })
+    }
+
+    /// Only the levels around the first unsafe step can make a report safe: removing any
+    /// earlier level keeps the step before it, removing any later level keeps the unsafe step.
+    fn is_safe_with_problem_dampener_linear(r: &[i64]) -> bool {
+        let Some(i) = first_unsafe_step(r) else {
+            return true;
+        };
+        (i.saturating_sub(1)..=i + 1).any(|skip| {
+            let mutation = [&r[..skip], &r[skip + 1..]].concat();
+            first_unsafe_step(&mutation).is_none()
+        })
+    }
+
+    fn part_two_linear(input: &str) -> Option<usize> {
+        let parsed = parse(input);
+        Some(
+            parsed
+                .iter()
+                .filter(|r| is_safe_with_problem_dampener_linear(r))
+                .count(),
+        )
+    }
+
+    /// Reports of 5 to 8 levels, mostly taking small steps so that many are (nearly) safe.
+    fn generate(g: &mut Gen) -> String {
+        let reports = g.vec(1..=20, |g| {
+            let mut level = g.i64(40..=60);
+            let mut levels = vec![level];
+            for _ in 0..g.usize(4..=7) {
+                let step = g.i64(0..=4);
+                level += if g.one_in(3) { -step } else { step };
+                levels.push(level);
+            }
+            levels.iter().join(" ")
+        });
+        reports.join("\n") + "\n"
+    }
 
     #[test]
     fn test_is_safe_1() {
@@ -160,6 +208,26 @@ mod tests {
         assert_eq!(result, Some(4));
     }
 
+    #[test]
+    fn test_part_two_linear() {
+        let result = part_two_linear(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(4));
+    }
+
+    #[test]
+    fn test_dampener_against_brute_force() {
+        differential(
+            |g| g.vec(2..=8, |g| g.i64(1..=12)),
+            |r| is_safe_with_problem_dampener_linear(r),
+            |r| is_safe_with_problem_dampener(r),
+        );
+        differential(
+            generate,
+            |input| part_two_linear(input),
+            |input| part_two(input),
+        );
+    }
+
     #[test]
     fn test_split_at() {
         let (left, right) = [1, 2, 3].split_at(0);
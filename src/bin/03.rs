@@ -3,7 +3,7 @@ advent_of_code::solution!(3);
 type Multiplication = (u32, u32);
 
 fn result(mults: &[Multiplication]) -> u32 {
-    mults.iter().map(|(x, y)| (x * y)).sum()
+    mults.iter().map(|(x, y)| x * y).sum()
 }
 
 fn parse_single(input: &str) -> (Option<Multiplication>, &str) {
@@ -1,9 +1,9 @@
 use itertools::Itertools;
 
-advent_of_code::solution!(4);
+advent_of_code::solution!(4, parse = parse);
 
 #[derive(Debug)]
-struct Input<'a>(Vec<&'a str>);
+pub struct Input<'a>(Vec<&'a str>);
 
 #[derive(Debug)]
 struct Vec2<T>(T, T);
@@ -99,13 +99,15 @@ fn x_marks_the_spot(point: &Point, input: &Input) -> usize {
     0
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let input = Input::from_str(input)?;
+pub fn parse(input: &str) -> Option<Input<'_>> {
+    Input::from_str(input)
+}
 
+pub fn part_one(input: &Input) -> Option<usize> {
     let mut sum = 0;
     for x in 0..input.columns() {
         for y in 0..input.rows() {
-            sum += x_marks_the_spot(&Vec2(x, y), &input);
+            sum += x_marks_the_spot(&Vec2(x, y), input);
         }
     }
     Some(sum)
@@ -134,13 +136,11 @@ fn mas_marks_the_x(point: &Point, input: &Input) -> usize {
     0
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let input = Input::from_str(input)?;
-
+pub fn part_two(input: &Input) -> Option<usize> {
     let mut sum = 0;
     for x in 0..input.columns() {
         for y in 0..input.rows() {
-            sum += mas_marks_the_x(&Vec2(x, y), &input);
+            sum += mas_marks_the_x(&Vec2(x, y), input);
         }
     }
     Some(sum)
@@ -152,13 +152,15 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_one(&parse(&input).unwrap());
         assert_eq!(result, Some(18));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_two(&parse(&input).unwrap());
         assert_eq!(result, Some(9));
     }
 }
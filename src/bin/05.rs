@@ -2,7 +2,74 @@ use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
-advent_of_code::solution!(5);
+use advent_of_code::parse::{ParseResult, Span};
+use advent_of_code::search::topological_sort;
+use advent_of_code::template::validate::Validator;
+
+advent_of_code::solution!(5, validate = validate);
+
+/// Rules and updates are separated by a blank line. Pages are two-digit numbers, updates have an
+/// odd number of distinct pages, and the rules put the pages of each update in a single order.
+fn validate(input: &mut Validator) {
+    let Some([rules, updates]) = input.check(input.input().sections_exact::<2>()) else {
+        return;
+    };
+
+    let mut ordered: HashSet<(u32, u32)> = HashSet::new();
+    for line in rules.lines() {
+        let Some((before, after)) = input.check(line.split_once('|')) else {
+            continue;
+        };
+        let before = input.unsigned_in(before, 10..=99);
+        let after = input.unsigned_in(after, 10..=99);
+        if let (Some(before), Some(after)) = (before, after) {
+            if before == after {
+                input.error(line, format!("page {before} can't come before itself"));
+            } else {
+                ordered.insert((before, after));
+            }
+        }
+    }
+
+    for line in updates.lines() {
+        let mut pages = vec![];
+        for page in line.split(',') {
+            let Some(number) = input.unsigned_in(page, 10..=99) else {
+                continue;
+            };
+            if pages.contains(&number) {
+                input.error(page, format!("page {number} appears twice in the update"));
+            }
+            pages.push(number);
+        }
+        if pages.len() != line.split(',').count() {
+            continue;
+        }
+
+        if pages.len().is_multiple_of(2) {
+            input.error(
+                line,
+                format!("expected an odd number of pages, found {}", pages.len()),
+            );
+        }
+        let successors = |page: &u32| {
+            pages
+                .iter()
+                .filter(|after| ordered.contains(&(*page, **after)))
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        if let Err(page) = topological_sort(pages.iter().copied(), successors) {
+            input.error(line, format!("the rules order page {page} before itself"));
+        } else if let Some((a, b)) = pages
+            .iter()
+            .tuple_combinations()
+            .find(|(a, b)| !ordered.contains(&(**a, **b)) && !ordered.contains(&(**b, **a)))
+        {
+            input.warning(line, format!("no rule orders pages {a} and {b}"));
+        }
+    }
+}
 
 #[derive(Debug)]
 struct Input {
@@ -10,37 +77,28 @@ struct Input {
     updates: Vec<Vec<u32>>,
 }
 
-fn parse(input: &str) -> Input {
-    let rules = input
+fn parse(input: &str) -> ParseResult<Input> {
+    let [rules, updates] = Span::new(input).sections_exact::<2>()?;
+
+    let rules = rules
         .lines()
-        .take_while(|l| l.contains('|'))
         .map(parse_rule_line)
-        .collect();
-
-    let updates = input
+        .collect::<Result<_, _>>()?;
+    let updates = updates
         .lines()
-        .skip_while(|l| l.contains('|'))
-        .skip(1)
         .map(parse_update_line)
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    Input { updates, rules }
+    Ok(Input { updates, rules })
 }
 
-fn parse_update_line(input: &str) -> Vec<u32> {
-    input
-        .split(',')
-        .map(|w| w.parse().expect("is number"))
-        .collect()
+fn parse_update_line(input: Span) -> ParseResult<Vec<u32>> {
+    input.list(',', Span::unsigned)
 }
 
-fn parse_rule_line(input: &str) -> (u32, u32) {
-    let mut rule_elem_iter = input.split('|').map(|w| w.parse().expect("is number"));
-
-    (
-        rule_elem_iter.next().expect("before"),
-        rule_elem_iter.next().expect("after"),
-    )
+fn parse_rule_line(input: Span) -> ParseResult<(u32, u32)> {
+    let (before, after) = input.split_once('|')?;
+    Ok((before.unsigned()?, after.unsigned()?))
 }
 
 struct RuleLookup {
@@ -50,16 +108,12 @@ struct RuleLookup {
 
 impl RuleLookup {
     fn new(input: &[(u32, u32)]) -> Self {
-        let mut befores = HashMap::new();
-        for (k, v) in &input.iter().map(|(b, a)| (*a, *b)).chunk_by(|(b, _)| *b) {
-            let v: HashSet<u32> = v.map(|t| t.1).collect();
-            befores.insert(k, v);
-        }
-
-        let mut afters = HashMap::new();
-        for (k, v) in &input.iter().map(|(b, a)| (*b, *a)).chunk_by(|(b, _)| *b) {
-            let v: HashSet<u32> = v.map(|t| t.1).collect();
-            afters.insert(k, v);
+        // rules of a page are not necessarily next to each other.
+        let mut befores: HashMap<u32, HashSet<u32>> = HashMap::new();
+        let mut afters: HashMap<u32, HashSet<u32>> = HashMap::new();
+        for (b, a) in input {
+            befores.entry(*a).or_default().insert(*b);
+            afters.entry(*b).or_default().insert(*a);
         }
 
         Self { befores, afters }
@@ -96,7 +150,7 @@ pub fn middle(v: &[u32]) -> u32 {
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let input = parse(input);
+    let input = parse(input).ok()?;
 
     let lookup = RuleLookup::new(&input.rules);
 
@@ -108,32 +162,19 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 fn fix_ordering(rules: &[(u32, u32)], update: &[u32]) -> Vec<u32> {
-    let mut res = update.to_vec();
-    let mut swapped = false;
-    loop {
-        for (before, after) in rules.iter() {
-            res.iter()
-                .position(|x| x == before)
-                .and_then(|before_index| {
-                    res.iter().position(|x| x == after).map(|after_index| {
-                        if before_index > after_index {
-                            res.swap(before_index, after_index);
-                            swapped = true;
-                        }
-                    })
-                });
-        }
-        if !swapped {
-            break;
-        }
-        swapped = false;
-    }
-
-    res
+    let afters = |page: &u32| {
+        rules
+            .iter()
+            .filter(|(before, _)| before == page)
+            .map(|(_, after)| *after)
+            .collect::<Vec<_>>()
+    };
+
+    topological_sort(update.iter().copied(), afters).expect("rules of an update are acyclic")
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let input = parse(input);
+    let input = parse(input).ok()?;
 
     let lookup = RuleLookup::new(&input.rules);
 
@@ -156,6 +197,51 @@ pub fn part_two(input: &str) -> Option<u32> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::property::{differential, Gen};
+    use advent_of_code::template::validate::Severity;
+
+    /// Rules that order every pair of pages in any order, followed by updates of an odd number
+    /// of distinct pages.
+    fn generate(g: &mut Gen) -> String {
+        let mut pages: Vec<u32> = (11..11 + g.u32(2..=12)).collect();
+        g.shuffle(&mut pages);
+
+        let mut rules: Vec<(u32, u32)> = pages.iter().copied().tuple_combinations().collect();
+        g.shuffle(&mut rules);
+
+        let updates = g.vec(1..=8, |g| {
+            let mut update = pages.clone();
+            g.shuffle(&mut update);
+            update.truncate(g.usize(0..=(pages.len() - 1) / 2) * 2 + 1);
+            update.iter().join(",")
+        });
+
+        let rules = rules
+            .iter()
+            .map(|(before, after)| format!("{before}|{after}"));
+        format!("{}\n\n{}\n", rules.format("\n"), updates.join("\n"))
+    }
+
+    fn is_valid_order_brute_force(rules: &[(u32, u32)], update: &[u32]) -> bool {
+        update
+            .iter()
+            .tuple_combinations()
+            .all(|(before, after)| !rules.contains(&(*after, *before)))
+    }
+
+    fn fix_ordering_brute_force(rules: &[(u32, u32)], update: &[u32]) -> Vec<u32> {
+        let mut fixed = update.to_vec();
+        fixed.sort_by(|a, b| {
+            if rules.contains(&(*a, *b)) {
+                std::cmp::Ordering::Less
+            } else if rules.contains(&(*b, *a)) {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        });
+        fixed
+    }
 
     #[test]
     fn test_middle() {
@@ -175,4 +261,58 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(123));
     }
+
+    #[test]
+    fn test_validate() {
+        let mut validator = Validator::new("47|53\n53|47\n61|61\n\n47,53,47\n61,75\n47,53,5\n");
+        validate(&mut validator);
+        let messages: Vec<String> = validator.finish().iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "error: line 3, column 1: page 61 can't come before itself",
+                "error: line 5, column 1: the rules order page 47 before itself",
+                "error: line 5, column 7: page 47 appears twice in the update",
+                "error: line 6, column 1: expected an odd number of pages, found 2",
+                "warning: line 6, column 1: no rule orders pages 61 and 75",
+                "error: line 7, column 7: expected a value in 10..=99, found 5",
+            ]
+        );
+
+        let example = advent_of_code::template::read_file("examples", DAY);
+        let mut validator = Validator::new(&example);
+        validate(&mut validator);
+        let diagnostics = validator.finish();
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+    }
+
+    #[test]
+    fn test_ordering_against_brute_force() {
+        let updates = |input: &String| {
+            let input = parse(input).expect("generated inputs are valid");
+            let lookup = RuleLookup::new(&input.rules);
+            input
+                .updates
+                .iter()
+                .map(|update| {
+                    let valid = lookup.is_valid_order(update);
+                    (valid, (!valid).then(|| fix_ordering(&input.rules, update)))
+                })
+                .collect::<Vec<_>>()
+        };
+        let updates_brute_force = |input: &String| {
+            let input = parse(input).expect("generated inputs are valid");
+            input
+                .updates
+                .iter()
+                .map(|update| {
+                    let valid = is_valid_order_brute_force(&input.rules, update);
+                    let fixed = fix_ordering_brute_force(&input.rules, update);
+                    (valid, (!valid).then_some(fixed))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        differential(generate, updates, updates_brute_force);
+    }
 }
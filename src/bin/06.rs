@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use advent_of_code::search::brent;
 use itertools::Itertools;
 
 advent_of_code::solution!(6);
@@ -118,27 +119,34 @@ impl State {
         }
     }
 
-    fn is_loop_configuration(
-        &mut self,
+    /// Where the guard walks next, or `None` once it leaves the map.
+    fn step(
         input: &Input,
+        (guard, direction): (Position, Direction),
         injected_obstacle: &Option<Position>,
-    ) -> bool {
-        let mut visited_with_direction = HashSet::new();
-        visited_with_direction.insert((self.guard, self.direction));
-        loop {
-            if self.is_out_of_bounds(input) {
-                return false;
-            }
-            if let Some(next) = self.get_next_position(input, injected_obstacle) {
-                if visited_with_direction.contains(&(next, self.direction)) {
-                    return true;
-                }
-                visited_with_direction.insert((next, self.direction));
-                self.guard = next;
-            } else {
-                return false;
-            }
+    ) -> Option<(Position, Direction)> {
+        let mut direction = direction;
+        let mut next = guard.add_signed(&direction.as_vec2())?;
+
+        let next_is_obstacle =
+            input.get(&next) == Some('#') || injected_obstacle.is_some_and(|io| next == io);
+        if next_is_obstacle {
+            direction = direction.turn_right();
+            next = guard
+                .add_signed(&direction.as_vec2())
+                .expect("should this happen?");
         }
+
+        input.get(&next)?;
+        Some((next, direction))
+    }
+
+    fn is_loop_configuration(&self, input: &Input, injected_obstacle: &Option<Position>) -> bool {
+        // leaving the map is a fixed point, whereas a loop always moves the guard along.
+        let walk = |guard: &Option<(Position, Direction)>| {
+            guard.and_then(|guard| Self::step(input, guard, injected_obstacle))
+        };
+        brent(Some((self.guard, self.direction)), walk).length > 1
     }
 
     fn is_out_of_bounds(&self, input: &Input) -> bool {
@@ -165,7 +173,7 @@ pub fn part_two(input: &str) -> Option<u32> {
 
     let mut sum = 0;
     for injected_obstacle in state_out_of_bounds.visited {
-        let mut state = State::initial(&input)?;
+        let state = State::initial(&input)?;
         if state.is_loop_configuration(&input, &Some(injected_obstacle)) {
             sum += 1;
         }
@@ -1,4 +1,9 @@
-advent_of_code::solution!(7);
+use advent_of_code::math::Digits;
+
+advent_of_code::solution!(
+    7,
+    variants = [part_one => [part_one_backward], part_two => [part_two_backward]]
+);
 
 struct Input(Vec<Equation>);
 
@@ -42,20 +47,6 @@ impl TryFrom<&str> for Equation {
     }
 }
 
-trait Concatable
-where
-    Self: Sized,
-{
-    fn concat(&self, other: &Self) -> Option<Self>;
-}
-
-impl Concatable for u64 {
-    fn concat(&self, other: &Self) -> Option<Self> {
-        let concat = format!("{}{}", self, other);
-        concat.parse().ok()
-    }
-}
-
 #[derive(Debug)]
 enum Operator {
     Add,
@@ -107,7 +98,7 @@ impl Solution {
                 self.operators.pop();
             }
             let concated = intermediate_result
-                .concat(component)
+                .concat(*component)
                 .expect("can be concated");
             if ops == OperatorSet::AddMultiplyConcat && concated <= equation.result {
                 self.operators.push(Operator::Concat);
@@ -161,6 +152,51 @@ pub fn part_two(input: &str) -> Option<u64> {
     Some(sum)
 }
 
+/// Works from the result back to the first component, undoing one operator per step.
+/// Most branches end early, as the result is rarely divisible by or ends in the component.
+fn is_solvable_backward(result: u64, components: &[u64], ops: OperatorSet) -> bool {
+    let Some((&last, rest)) = components.split_last() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return result == last;
+    }
+
+    if result >= last && is_solvable_backward(result - last, rest, ops) {
+        return true;
+    }
+    if last != 0 && result.is_multiple_of(last) && is_solvable_backward(result / last, rest, ops) {
+        return true;
+    }
+    if ops == OperatorSet::AddMultiplyConcat {
+        let (prefix, suffix) = result.split_digits(last.digit_count());
+        if suffix == last && is_solvable_backward(prefix, rest, ops) {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn sum_solvable_backward(input: &str, ops: OperatorSet) -> u64 {
+    let input: Input = input.try_into().expect("can be parsed");
+
+    input
+        .0
+        .iter()
+        .filter(|equation| is_solvable_backward(equation.result, &equation.components, ops))
+        .map(|equation| equation.result)
+        .sum()
+}
+
+pub fn part_one_backward(input: &str) -> Option<u64> {
+    Some(sum_solvable_backward(input, OperatorSet::AddMultiply))
+}
+
+pub fn part_two_backward(input: &str) -> Option<u64> {
+    Some(sum_solvable_backward(input, OperatorSet::AddMultiplyConcat))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -176,4 +212,11 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(11387));
     }
+
+    #[test]
+    fn test_backward_variants() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        assert_eq!(part_one_backward(&input), Some(3749));
+        assert_eq!(part_two_backward(&input), Some(11387));
+    }
 }
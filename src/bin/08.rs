@@ -1,8 +1,24 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    str::FromStr,
+};
 
+use advent_of_code::math::gcd;
 use itertools::Itertools;
 
-advent_of_code::solution!(8);
+advent_of_code::solution!(8, params = Params);
+
+advent_of_code::params! {
+    pub struct Params {
+        antinodes: AntinodeMode = {
+            input: AntinodeMode::GeometricallyComplete,
+            example: AntinodeMode::GeometricallyComplete,
+        },
+        /// Prints the map with the antinodes of each part overlaid.
+        render: bool = { input: false, example: false },
+    }
+}
 
 #[derive(Debug, Hash, Ord, PartialEq, PartialOrd, Eq, Clone, Copy)]
 struct Vec2<T>(T, T);
@@ -93,46 +109,92 @@ fn antinodes_for_groups_first<'a>(
         .flatten()
 }
 
+/// How the resonant harmonics of an antenna pair are traced.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AntinodeMode {
+    /// Step by the raw distance between the two antennas, as the puzzle text describes.
+    PuzzleLiteral,
+    /// Step by the distance reduced by its gcd, hitting every grid point on the line.
+    GeometricallyComplete,
+}
+
+impl FromStr for AntinodeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "literal" => Ok(Self::PuzzleLiteral),
+            "complete" => Ok(Self::GeometricallyComplete),
+            _ => Err(format!("unknown antinode mode: {}", s)),
+        }
+    }
+}
+
+/// Walks from a starting position in a fixed step until leaving the map.
+struct Ray {
+    next: Option<Position>,
+    step: Direction,
+    dimensions: Position,
+}
+
+impl Iterator for Ray {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self
+            .next
+            .filter(|p| p.0 < self.dimensions.0 && p.1 < self.dimensions.1)?;
+        self.next = current.add_signed(&self.step);
+        Some(current)
+    }
+}
+
+impl Direction {
+    fn reduced(&self) -> Self {
+        let divisor = gcd(self.0, self.1).max(1);
+        Vec2(self.0 / divisor, self.1 / divisor)
+    }
+
+    fn negate(&self) -> Self {
+        Vec2(-self.0, -self.1)
+    }
+}
+
+fn rays_for_pair(
+    a: &Position,
+    b: &Position,
+    dimensions: &Position,
+    mode: AntinodeMode,
+) -> [Ray; 2] {
+    let ray = |next, step| Ray {
+        next,
+        step,
+        dimensions: *dimensions,
+    };
+
+    match mode {
+        AntinodeMode::PuzzleLiteral => [ray(Some(*a), a.subtract(b)), ray(Some(*b), b.subtract(a))],
+        AntinodeMode::GeometricallyComplete => {
+            let step = a.subtract(b).reduced();
+            let backwards = step.negate();
+            [
+                ray(Some(*a), step),
+                ray(a.add_signed(&backwards), backwards),
+            ]
+        }
+    }
+}
+
 fn antinodes_for_groups_all<'a>(
     antennas: &'a [&'a Antenna],
-    dimensions: &Position,
-) -> Vec<Position> {
+    dimensions: &'a Position,
+    mode: AntinodeMode,
+) -> impl Iterator<Item = Position> + use<'a> {
     antennas
         .iter()
         .tuple_combinations()
-        .flat_map(|(a, b)| {
-            let delta_ab = a.position.subtract(&b.position);
-
-            let delta_ba = b.position.subtract(&a.position);
-
-            let mut result = vec![a.position, b.position];
-
-            let mut antinode_a = b.position.add_signed(&delta_ba);
-            loop {
-                if let Some(an_a) = antinode_a {
-                    if an_a.0 < dimensions.0 && an_a.1 < dimensions.1 {
-                        result.push(an_a);
-                        antinode_a = an_a.add_signed(&delta_ba);
-                        continue;
-                    }
-                }
-                break;
-            }
-            let mut antinode_b = a.position.add_signed(&delta_ab);
-            loop {
-                if let Some(an_b) = antinode_b {
-                    if an_b.0 < dimensions.0 && an_b.1 < dimensions.1 {
-                        result.push(an_b);
-                        antinode_b = an_b.add_signed(&delta_ab);
-                        continue;
-                    }
-                }
-                break;
-            }
-
-            result.into_iter()
-        })
-        .collect()
+        .flat_map(move |(a, b)| rays_for_pair(&a.position, &b.position, dimensions, mode))
+        .flatten()
 }
 
 impl Input {
@@ -159,43 +221,143 @@ impl Input {
         antinodes
     }
 
-    fn antinodes_all(&self) -> HashSet<Position> {
+    fn antinodes_all(&self, mode: AntinodeMode) -> HashSet<Position> {
         let mut antinodes = HashSet::new();
         let group_by_type = self.group_by_type();
         for (_, chunk) in group_by_type {
-            for anti in antinodes_for_groups_all(&chunk, &self.dimensions) {
-                antinodes.insert(anti);
-            }
+            antinodes.extend(antinodes_for_groups_all(&chunk, &self.dimensions, mode));
         }
         antinodes
     }
+
+    fn with_antinodes<'a>(&'a self, antinodes: &'a HashSet<Position>) -> AntinodeMap<'a> {
+        AntinodeMap {
+            input: self,
+            antinodes,
+        }
+    }
+}
+
+/// Renders the map with antinodes overlaid, antennas taking precedence.
+struct AntinodeMap<'a> {
+    input: &'a Input,
+    antinodes: &'a HashSet<Position>,
+}
+
+impl Display for AntinodeMap<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let antennas: HashMap<Position, char> = self
+            .input
+            .antennas
+            .iter()
+            .map(|a| (a.position, a.typ))
+            .collect();
+
+        for y in 0..self.input.dimensions.1 {
+            for x in 0..self.input.dimensions.0 {
+                let p = Vec2(x, y);
+                let c = match antennas.get(&p) {
+                    Some(typ) => *typ,
+                    None if self.antinodes.contains(&p) => '#',
+                    None => '.',
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
+pub fn part_one(input: &str, params: &Params) -> Option<usize> {
     let input: Input = input.try_into().ok()?;
     let antinodes = input.antinodes_first();
+    if params.render {
+        print!("{}", input.with_antinodes(&antinodes));
+    }
     antinodes.len().into()
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
+pub fn part_two(input: &str, params: &Params) -> Option<usize> {
     let input: Input = input.try_into().ok()?;
-    let antinodes = input.antinodes_all();
+    let antinodes = input.antinodes_all(params.antinodes);
+    if params.render {
+        print!("{}", input.with_antinodes(&antinodes));
+    }
     antinodes.len().into()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::params::Params as _;
+
+    const T_EXAMPLE: &str = r"T.........
+...T......
+.T........
+..........
+..........
+..........
+..........
+..........
+..........
+..........";
+
+    #[test]
+    fn test_direction_reduced() {
+        assert_eq!(Vec2(4, -6).reduced(), Vec2(2, -3));
+        assert_eq!(Vec2(0, -3).reduced(), Vec2(0, -1));
+        assert_eq!(Vec2(3, 5).reduced(), Vec2(3, 5));
+    }
+
+    #[test]
+    fn test_antinode_modes_with_common_factor() {
+        let input: Input = "a....\n.....\n..a..\n.....\n.....".try_into().unwrap();
+
+        let literal = input.antinodes_all(AntinodeMode::PuzzleLiteral);
+        assert_eq!(literal.len(), 3);
+
+        let complete = input.antinodes_all(AntinodeMode::GeometricallyComplete);
+        assert_eq!(complete.len(), 5);
+        assert!(complete.contains(&Vec2(1, 1)));
+        assert!(complete.contains(&Vec2(3, 3)));
+    }
+
+    #[test]
+    fn test_display_antinodes() {
+        let input: Input = T_EXAMPLE.try_into().unwrap();
+        let antinodes = input.antinodes_all(AntinodeMode::GeometricallyComplete);
+        assert_eq!(antinodes.len(), 9);
+
+        let expected = r"T....#....
+...T......
+.T....#...
+.........#
+..#.......
+..........
+...#......
+..........
+....#.....
+..........
+";
+        assert_eq!(input.with_antinodes(&antinodes).to_string(), expected);
+    }
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(
+            &advent_of_code::template::read_file("examples", DAY),
+            &Params::example(),
+        );
         assert_eq!(result, Some(14));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(
+            &advent_of_code::template::read_file("examples", DAY),
+            &Params::example(),
+        );
         assert_eq!(result, Some(34));
     }
 }
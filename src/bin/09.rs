@@ -22,26 +22,17 @@ impl Disk {
         for (index, count_blocks) in input.iter().enumerate() {
             let is_free = index % 2 != 0;
             let block = if is_free { None } else { Some(index / 2) };
-            iter::repeat(block)
-                .take(*count_blocks)
-                .for_each(|_| disk.push(block));
+            iter::repeat_n(block, *count_blocks).for_each(|_| disk.push(block));
         }
         Self(disk)
     }
 
-    fn first_free_last_occupied(&self) -> (usize, usize) {
-        let free = self
-            .0
-            .iter()
-            .position(|block| block.is_none())
-            .expect("should have a free block");
-        let used = self
-            .0
-            .iter()
-            .rposition(|block| block.is_some())
-            .expect("should have a used block");
+    /// `None` if the disk is full or empty.
+    fn first_free_last_occupied(&self) -> Option<(usize, usize)> {
+        let free = self.0.iter().position(|block| block.is_none())?;
+        let used = self.0.iter().rposition(|block| block.is_some())?;
 
-        (free, used)
+        Some((free, used))
     }
 
     fn checksum(&self) -> usize {
@@ -122,10 +113,11 @@ pub fn part_one(input: &str) -> Option<usize> {
     let mut disk = Disk::from_input(&parsed);
 
     // defrag
-    let (mut free, mut used) = disk.first_free_last_occupied();
-    while free < used {
+    while let Some((free, used)) = disk.first_free_last_occupied() {
+        if free > used {
+            break;
+        }
         disk.0.swap(free, used);
-        (free, used) = disk.first_free_last_occupied();
     }
 
     Some(disk.checksum())
@@ -157,6 +149,62 @@ pub fn part_two(input: &str) -> Option<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::property::{differential, Gen};
+
+    /// Moves blocks from the end into the leftmost free space until the two meet.
+    fn part_one_two_pointers(input: &str) -> Option<usize> {
+        let parsed: Vec<_> = parse(input).collect();
+
+        let mut disk = Disk::from_input(&parsed).0;
+
+        let (mut free, mut used) = (0, disk.len());
+        loop {
+            while free < used && disk[free].is_some() {
+                free += 1;
+            }
+            while used > free && disk[used - 1].is_none() {
+                used -= 1;
+            }
+            if used <= free + 1 {
+                break;
+            }
+            disk.swap(free, used - 1);
+        }
+
+        Some(Disk(disk).checksum())
+    }
+
+    /// Disk maps of files with 1 to 9 blocks, separated by up to 9 free blocks.
+    fn generate(g: &mut Gen) -> String {
+        let sizes = g.vec(1..=30, |g| [g.usize(1..=9), g.usize(0..=9)]).concat();
+        let mut map: String = sizes.iter().map(|size| size.to_string()).collect();
+        if g.one_in(2) {
+            // puzzle inputs end with a file, trailing free space is only tested half of the time.
+            map.pop();
+        }
+        map + "\n"
+    }
+
+    #[test]
+    fn test_part_one_two_pointers() {
+        let result = part_one_two_pointers(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(1928));
+    }
+
+    #[test]
+    fn test_part_one_full_disk() {
+        assert_eq!(part_one("303\n"), Some(12));
+        assert_eq!(part_one_two_pointers("303\n"), Some(12));
+    }
+
+    #[test]
+    fn test_compaction_against_swapping() {
+        differential(
+            generate,
+            |input| part_one_two_pointers(input),
+            |input| part_one(input),
+        );
+    }
 
     #[test]
     fn test_part_one() {
@@ -1,8 +1,15 @@
-use std::collections::HashSet;
+use std::fmt::Display;
 
 use itertools::Itertools;
 
-advent_of_code::solution!(10);
+advent_of_code::solution!(10, params = Params);
+
+advent_of_code::params! {
+    pub struct Params {
+        /// Prints the score and rating of every trailhead.
+        report: bool = { input: false, example: false },
+    }
+}
 
 #[derive(Debug, Hash, Ord, PartialEq, PartialOrd, Eq, Clone, Copy)]
 struct Vec2<T>(T, T);
@@ -64,46 +71,151 @@ impl Input {
         self.0.first().expect("not empty").len()
     }
 
-    fn trailhead_score_by_destination(&self, p: &Position) -> usize {
-        let mut visited_peaks: HashSet<Position> = HashSet::new();
-        self.trailhead_helper(p, 0, &mut |p| {
-            visited_peaks.insert(*p);
-        });
-        visited_peaks.len()
+    fn positions(&self) -> impl Iterator<Item = Position> + use<'_> {
+        (0..self.rows()).flat_map(|row| (0..self.columns()).map(move |column| Vec2(column, row)))
     }
 
-    fn trailhead_score_by_path(&self, p: &Position) -> Option<usize> {
-        self.trailhead_helper(p, 0, &mut |_| {})
+    fn index(&self, p: &Position) -> usize {
+        p.1 * self.columns() + p.0
     }
 
-    fn trailhead_helper(
-        &self,
-        p: &Position,
-        level: u8,
-        peak_visitor: &mut impl FnMut(&Position),
-    ) -> Option<usize> {
-        let value = self.get(p)?;
-        if value != level {
-            return None;
+    #[cfg(test)]
+    fn trailhead_score_by_destination(&self, p: &Position) -> usize {
+        self.trails()
+            .trailheads
+            .iter()
+            .find(|t| t.position == *p)
+            .map_or(0, |t| t.score)
+    }
+
+    /// Sweeps the map from height 9 down to 0, accumulating for every cell the number of
+    /// distinct hiking trails (rating) and the set of peaks reachable from it (score).
+    fn trails(&self) -> Trails {
+        let cells = self.rows() * self.columns();
+
+        let mut by_height: [Vec<Position>; 10] = Default::default();
+        for p in self.positions() {
+            if let Some(height) = self.get(&p) {
+                by_height[height as usize].push(p);
+            }
+        }
+
+        let peak_count = by_height[9].len();
+        let mut ratings = vec![0; cells];
+        let mut peaks = vec![PeakSet::new(peak_count); cells];
+
+        for (peak_index, peak) in by_height[9].iter().enumerate() {
+            let index = self.index(peak);
+            ratings[index] = 1;
+            peaks[index].insert(peak_index);
+        }
+
+        for height in (0..9).rev() {
+            for p in &by_height[height as usize] {
+                let index = self.index(p);
+                for next_p in [
+                    Direction::Up,
+                    Direction::Down,
+                    Direction::Left,
+                    Direction::Right,
+                ]
+                .into_iter()
+                .filter_map(|d| p.add_direction(d))
+                {
+                    if self.get(&next_p) != Some(height + 1) {
+                        continue;
+                    }
+                    let next_index = self.index(&next_p);
+                    ratings[index] += ratings[next_index];
+                    let (current, next) = if index < next_index {
+                        let (left, right) = peaks.split_at_mut(next_index);
+                        (&mut left[index], &right[0])
+                    } else {
+                        let (left, right) = peaks.split_at_mut(index);
+                        (&mut right[0], &left[next_index])
+                    };
+                    current.union_with(next);
+                }
+            }
         }
 
-        if value == 9 {
-            peak_visitor(p);
-            return Some(1);
+        let trailheads = by_height[0]
+            .iter()
+            .map(|p| {
+                let index = self.index(p);
+                Trailhead {
+                    position: *p,
+                    score: peaks[index].len(),
+                    rating: ratings[index],
+                }
+            })
+            .collect();
+
+        Trails { trailheads }
+    }
+}
+
+/// A fixed-size set of peak indices, stored as a bitset.
+#[derive(Debug, Clone)]
+struct PeakSet(Vec<u64>);
+
+impl PeakSet {
+    fn new(peaks: usize) -> Self {
+        Self(vec![0; peaks.div_ceil(64)])
+    }
+
+    fn insert(&mut self, peak: usize) {
+        self.0[peak / 64] |= 1 << (peak % 64);
+    }
+
+    fn union_with(&mut self, other: &PeakSet) {
+        for (word, other_word) in self.0.iter_mut().zip(&other.0) {
+            *word |= other_word;
         }
+    }
 
-        let next_level = level + 1;
-        let result = [
-            Direction::Up,
-            Direction::Down,
-            Direction::Left,
-            Direction::Right,
-        ]
-        .into_iter()
-        .filter_map(|d| p.add_direction(d))
-        .filter_map(|next_p| self.trailhead_helper(&next_p, next_level, peak_visitor))
-        .sum();
-        Some(result)
+    fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Trailhead {
+    position: Position,
+    score: usize,
+    rating: usize,
+}
+
+struct Trails {
+    trailheads: Vec<Trailhead>,
+}
+
+impl Trails {
+    fn total_score(&self) -> usize {
+        self.trailheads.iter().map(|t| t.score).sum()
+    }
+
+    fn total_rating(&self) -> usize {
+        self.trailheads.iter().map(|t| t.rating).sum()
+    }
+}
+
+/// Lists every trailhead with its score and rating.
+impl Display for Trails {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for trailhead in &self.trailheads {
+            writeln!(
+                f,
+                "({}, {}): score {}, rating {}",
+                trailhead.position.0, trailhead.position.1, trailhead.score, trailhead.rating
+            )?;
+        }
+        write!(
+            f,
+            "total: score {}, rating {}",
+            self.total_score(),
+            self.total_rating()
+        )
     }
 }
 
@@ -120,42 +232,24 @@ impl TryFrom<&str> for Input {
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
+pub fn part_one(input: &str, params: &Params) -> Option<usize> {
     let input: Input = input.try_into().ok()?;
-
-    let mut sum = 0;
-    for row in 0..input.rows() {
-        for column in 0..input.columns() {
-            let p = Vec2(column, row);
-            let score = input.trailhead_score_by_destination(&p);
-            if score > 0 {
-                sum += score;
-            }
-        }
+    let trails = input.trails();
+    if params.report {
+        println!("{trails}");
     }
-
-    Some(sum)
+    Some(trails.total_score())
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
+pub fn part_two(input: &str, _params: &Params) -> Option<usize> {
     let input: Input = input.try_into().ok()?;
-
-    let mut sum = 0;
-    for row in 0..input.rows() {
-        for column in 0..input.columns() {
-            let p = Vec2(column, row);
-            if let Some(score) = input.trailhead_score_by_path(&p) {
-                sum += score;
-            }
-        }
-    }
-
-    Some(sum)
+    Some(input.trails().total_rating())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::params::Params as _;
 
     #[test]
     fn test_example_1() {
@@ -191,6 +285,45 @@ mod tests {
         assert_eq!(trailhead_score, 4);
     }
 
+    #[test]
+    fn test_trails_report() {
+        let input: Input = r"..90..9
+...1.98
+...2..7
+6543456
+765.987
+876....
+987...."
+            .try_into()
+            .expect("parses");
+
+        let trails = input.trails();
+        assert_eq!(
+            trails.trailheads,
+            vec![Trailhead {
+                position: Vec2(3, 0),
+                score: 4,
+                rating: 13,
+            }]
+        );
+        assert_eq!(
+            trails.to_string(),
+            "(3, 0): score 4, rating 13\ntotal: score 4, rating 13"
+        );
+    }
+
+    #[test]
+    fn test_peak_set_spans_words() {
+        let mut a = PeakSet::new(130);
+        let mut b = PeakSet::new(130);
+        a.insert(0);
+        a.insert(64);
+        b.insert(64);
+        b.insert(129);
+        a.union_with(&b);
+        assert_eq!(a.len(), 3);
+    }
+
     #[test]
     fn test_vec2_underflow() {
         let o: Position = Vec2(0, 0);
@@ -201,13 +334,19 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(
+            &advent_of_code::template::read_file("examples", DAY),
+            &Params::example(),
+        );
         assert_eq!(result, Some(36));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(
+            &advent_of_code::template::read_file("examples", DAY),
+            &Params::example(),
+        );
         assert_eq!(result, Some(81));
     }
 }
@@ -1,8 +1,16 @@
 use std::{collections::HashMap, mem};
 
+use advent_of_code::math::Digits;
 use itertools::Itertools;
 
-advent_of_code::solution!(11);
+advent_of_code::solution!(11, params = Params);
+
+advent_of_code::params! {
+    pub struct Params {
+        part_one_blinks: usize = { input: 25, example: 25 },
+        part_two_blinks: usize = { input: 75, example: 75 },
+    }
+}
 
 fn parse(input: &str) -> Vec<usize> {
     input
@@ -11,18 +19,6 @@ fn parse(input: &str) -> Vec<usize> {
         .collect()
 }
 
-fn splits_even(stone: usize) -> Option<(usize, usize)> {
-    let num_digits = stone.ilog10() + 1;
-    if num_digits % 2 == 0 {
-        let mid = num_digits / 2;
-        let divisor = 10usize.pow(mid);
-        let first = stone / divisor;
-        let second = stone % divisor;
-        return Some((first, second));
-    }
-    None
-}
-
 struct Solution(HashMap<usize, usize>);
 
 impl Solution {
@@ -50,7 +46,7 @@ impl Solution {
         for (stone, count) in prev.into_iter() {
             if stone == 0 {
                 self.add_count_to_stone(1, count);
-            } else if let Some((first, second)) = splits_even(stone) {
+            } else if let Some((first, second)) = stone.split_in_half() {
                 self.add_count_to_stone(first, count);
                 self.add_count_to_stone(second, count);
             } else {
@@ -64,31 +60,38 @@ impl Solution {
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
+pub fn part_one(input: &str, params: &Params) -> Option<usize> {
     let mut solution = Solution::new(&parse(input));
-    solution.blink_times(25);
+    solution.blink_times(params.part_one_blinks);
     Some(solution.result())
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
+pub fn part_two(input: &str, params: &Params) -> Option<usize> {
     let mut solution = Solution::new(&parse(input));
-    solution.blink_times(75);
+    solution.blink_times(params.part_two_blinks);
     Some(solution.result())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::params::Params as _;
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(
+            &advent_of_code::template::read_file("examples", DAY),
+            &Params::example(),
+        );
         assert_eq!(result, Some(55312));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(
+            &advent_of_code::template::read_file("examples", DAY),
+            &Params::example(),
+        );
         assert_eq!(result, Some(65601038650482));
     }
 }
@@ -1,6 +1,14 @@
+use advent_of_code::parse::{ParseError, Span};
 use itertools::Itertools;
 
-advent_of_code::solution!(14);
+advent_of_code::solution!(14, params = Params);
+
+advent_of_code::params! {
+    pub struct Params {
+        columns: u64 = { input: 101, example: 11 },
+        rows: u64 = { input: 103, example: 7 },
+    }
+}
 
 #[derive(Debug, PartialEq, Eq)]
 struct Bounds {
@@ -70,39 +78,20 @@ impl Vec2 {
     /// assert_eq!(Vec2::parse_with_prefix::<'p'>("p=0,4"), Ok(Vec2(0,4)));
     /// assert_eq!(Vec2::parse_with_prefix::<'v'>("v=-1,2"), Ok(Vec2(-1,2)));
     /// ```
-    fn parse_with_prefix<const PREFIX: char>(input: &str) -> Result<Self, String> {
-        let (prefix, vec) = input.split_once('=').ok_or("missing =")?;
-        if prefix.len() != 1 {
-            return Err(format!(
-                "invalid prefix length (expected {}, got {})",
-                1,
-                prefix.len()
-            ));
-        }
-        let actual_prefix = prefix.chars().next().unwrap();
-        if actual_prefix != PREFIX {
-            return Err(format!(
-                "invalid prefix (expected {}, got {})",
-                PREFIX, actual_prefix
-            ));
-        }
-
-        let vec = vec.try_into()?;
-
-        Ok(vec)
+    fn parse_with_prefix<'a, const PREFIX: char>(
+        input: impl Into<Span<'a>>,
+    ) -> Result<Self, String> {
+        let vec = input.into().field(&PREFIX.to_string(), '=')?;
+        Ok(vec.try_into()?)
     }
 }
 
-impl TryFrom<&str> for Vec2 {
-    type Error = String;
+impl TryFrom<Span<'_>> for Vec2 {
+    type Error = ParseError;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let (x, y) = value.split_once(",").ok_or("expected comma")?;
-
-        let x = x.parse().map_err(|_| format!("failed to parse: '{}'", x))?;
-        let y = y.parse().map_err(|_| format!("failed to parse: '{}'", y))?;
-
-        Ok(Self(x, y))
+    fn try_from(value: Span<'_>) -> Result<Self, Self::Error> {
+        let (x, y) = value.split_once(',')?;
+        Ok(Self(x.signed()?, y.signed()?))
     }
 }
 
@@ -111,11 +100,11 @@ struct Robot {
     direction: Vec2,
 }
 
-impl TryFrom<&str> for Robot {
+impl TryFrom<Span<'_>> for Robot {
     type Error = String;
 
-    fn try_from(line: &str) -> Result<Self, Self::Error> {
-        let (position, direction) = line.split_once(' ').ok_or("missing space")?;
+    fn try_from(line: Span<'_>) -> Result<Self, Self::Error> {
+        let (position, direction) = line.split_once(' ')?;
         let position = Vec2::parse_with_prefix::<'p'>(position)?;
         let direction = Vec2::parse_with_prefix::<'v'>(direction)?;
 
@@ -186,7 +175,7 @@ impl TryFrom<&str> for Input {
     type Error = String;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let robots = value
+        let robots = Span::new(value)
             .lines()
             .map(|line| line.try_into())
             .collect::<Result<_, _>>()?;
@@ -194,14 +183,24 @@ impl TryFrom<&str> for Input {
     }
 }
 
-fn part_one_with_bounds(input: &str, bounds: &Bounds) -> Option<usize> {
+impl From<&Params> for Bounds {
+    fn from(params: &Params) -> Self {
+        Self {
+            columns: params.columns,
+            rows: params.rows,
+        }
+    }
+}
+
+pub fn part_one(input: &str, params: &Params) -> Option<usize> {
+    let bounds = Bounds::from(params);
     let input: Input = input.try_into().expect("parses");
     let end_positions = input
         .0
         .iter()
         .map(|robot| {
             let direction = robot.direction.mult(100);
-            robot.position.add_capped(&direction, bounds)
+            robot.position.add_capped(&direction, &bounds)
         })
         .collect::<Vec<_>>();
 
@@ -210,23 +209,10 @@ fn part_one_with_bounds(input: &str, bounds: &Bounds) -> Option<usize> {
     Some(q1 * q2 * q3 * q4)
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    part_one_with_bounds(
-        input,
-        &Bounds {
-            columns: 101,
-            rows: 103,
-        },
-    )
-}
-
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(input: &str, params: &Params) -> Option<u32> {
     let mut input: Input = input.try_into().expect("parses");
 
-    let bounds = Bounds {
-        columns: 101,
-        rows: 103,
-    };
+    let bounds = Bounds::from(params);
     let mut t = 0;
     loop {
         if input.has_frame_top_and_bottom(&bounds) && input.has_frame_sides(&bounds) {
@@ -243,6 +229,7 @@ pub fn part_two(input: &str) -> Option<u32> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::params::Params as _;
 
     #[test]
     fn test_bounds_count_quadrants() {
@@ -299,12 +286,9 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one_with_bounds(
+        let result = part_one(
             &advent_of_code::template::read_file("examples", DAY),
-            &Bounds {
-                columns: 11,
-                rows: 7,
-            },
+            &Params::example(),
         );
         assert_eq!(result, Some(12));
     }
@@ -1,5 +1,6 @@
 use std::{fmt::Display, iter};
 
+use advent_of_code::parse::Span;
 use itertools::Itertools;
 
 advent_of_code::solution!(15);
@@ -57,27 +58,14 @@ struct Input {
 }
 
 impl Input {
-    fn parse_tile_line(line: &str) -> Result<Vec<Tile>, String> {
-        line.chars()
-            .map(|c| match c {
-                '.' => Ok(Tile::Empty),
-                '@' => Ok(Tile::Robot),
-                '#' => Ok(Tile::Wall),
-                'O' => Ok(Tile::Box),
-                _ => Err(format!("invalid tile character: {}", c)),
-            })
-            .collect()
-    }
-
-    fn parse_tiles<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Vec<Vec<Tile>>, String> {
-        let lines: Vec<Vec<Tile>> = lines
-            .map(Input::parse_tile_line)
-            .collect::<Result<Vec<_>, _>>()?;
-        let lengths = lines.iter().map(|l| l.len()).dedup().count();
-        if lengths != 1 {
-            return Err("lines are not equal length".to_string());
-        }
-        Ok(lines)
+    fn parse_tiles(tiles: Span) -> Result<Vec<Vec<Tile>>, String> {
+        Ok(tiles.grid(|c| match c {
+            '.' => Some(Tile::Empty),
+            '@' => Some(Tile::Robot),
+            '#' => Some(Tile::Wall),
+            'O' => Some(Tile::Box),
+            _ => None,
+        })?)
     }
 
     fn parse_moves<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Vec<Direction>, String> {
@@ -180,11 +168,9 @@ impl TryFrom<&str> for Input {
     type Error = String;
 
     fn try_from(input: &str) -> Result<Self, Self::Error> {
-        let tile_lines = input.lines().take_while(|l| !l.is_empty());
-        let tiles = Input::parse_tiles(tile_lines)?;
-
-        let move_lines = input.lines().skip_while(|l| !l.is_empty()).skip(1);
-        let moves = Input::parse_moves(move_lines)?;
+        let [tiles, moves] = Span::new(input).sections_exact::<2>()?;
+        let tiles = Input::parse_tiles(tiles)?;
+        let moves = Input::parse_moves(moves.lines().map(|line| line.as_str()))?;
 
         Ok(Self { tiles, moves })
     }
@@ -215,7 +201,7 @@ pub fn part_one(input: &str) -> Option<usize> {
     Some(input.box_gps_coords().into_iter().sum())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(_input: &str) -> Option<u32> {
     None
 }
 
@@ -235,7 +221,7 @@ mod tests {
 ########
 
 <^^>>>vv<v>>v<<"#;
-        let result = part_one(&input);
+        let result = part_one(input);
         assert_eq!(result, Some(2028));
     }
 
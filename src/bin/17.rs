@@ -1,6 +1,54 @@
+use advent_of_code::parse::{ParseError, Span};
+use advent_of_code::template::validate::Validator;
 use itertools::Itertools;
 
-advent_of_code::solution!(17);
+advent_of_code::solution!(17, validate = validate);
+
+/// Registers A, B and C, then a program of 3-bit opcode and operand pairs.
+fn validate(input: &mut Validator) {
+    let Some([registers, program]) = input.check(input.input().sections_exact::<2>()) else {
+        return;
+    };
+
+    let mut lines = registers.lines();
+    for name in ['A', 'B', 'C'] {
+        match lines.next() {
+            Some(line) => {
+                let value = line.field(&format!("Register {name}"), ':');
+                input.check(value.and_then(Span::unsigned::<usize>));
+            }
+            None => input.error(registers, format!("missing register {name}")),
+        }
+    }
+    for line in lines {
+        input.error(line, "expected only registers A, B and C");
+    }
+
+    let Some(values) = input.check(program.field("Program", ':')) else {
+        return;
+    };
+    let values: Vec<Span> = values.split(',').collect();
+    let numbers: Vec<Option<u8>> = values
+        .iter()
+        .map(|value| input.unsigned_in(*value, 0..=7))
+        .collect();
+
+    if !numbers.len().is_multiple_of(2) {
+        input.error(
+            program,
+            format!(
+                "expected pairs of opcodes and operands, found {} numbers",
+                numbers.len()
+            ),
+        );
+    }
+    for (index, pair) in numbers.chunks_exact(2).enumerate() {
+        // adv, bst, out, bdv and cdv take a combo operand, of which 7 is reserved.
+        if let [Some(0 | 2 | 5 | 6 | 7), Some(7)] = pair {
+            input.error(values[index * 2 + 1], "combo operand 7 is reserved");
+        }
+    }
+}
 
 #[derive(Debug, Eq, PartialEq)]
 enum State {
@@ -22,7 +70,8 @@ impl TryFrom<&str> for Computer {
     type Error = String;
 
     fn try_from(input: &str) -> Result<Self, Self::Error> {
-        let mut lines = input.lines();
+        let [registers, program] = Span::new(input).sections_exact::<2>()?;
+        let mut lines = registers.lines();
 
         let reg_a = lines.next().ok_or("needed line for reg a")?;
         let reg_a = Computer::parse_register::<'A'>(reg_a)?;
@@ -33,30 +82,9 @@ impl TryFrom<&str> for Computer {
         let reg_c = lines.next().ok_or("needed line for reg c")?;
         let reg_c = Computer::parse_register::<'C'>(reg_c)?;
 
-        let blank = lines.next();
-        if blank.is_none() || !blank.unwrap().is_empty() {
-            return Err("needed blank line".to_string());
-        }
+        let instructions = program.field("Program", ':')?.list(',', Span::unsigned)?;
 
-        if let Some(program_line) = lines.next() {
-            let (program, instructions) = program_line
-                .split_once(':')
-                .ok_or(format!("needed a program line with colon"))?;
-
-            if program != "Program" {
-                return Err("needed program line".to_string());
-            }
-
-            let instructions = instructions
-                .trim()
-                .split(',')
-                .map(|s| s.parse().unwrap())
-                .collect();
-
-            Ok(Computer::new(instructions, reg_a, reg_b, reg_c))
-        } else {
-            Err("needed program line".to_string())
-        }
+        Ok(Computer::new(instructions, reg_a, reg_b, reg_c))
     }
 }
 
@@ -72,18 +100,8 @@ impl Computer {
         }
     }
 
-    fn parse_register<const PREFIX: char>(line: &str) -> Result<usize, String> {
-        let (reg, val) = line
-            .split_once(':')
-            .ok_or(format!("could not split on : for input '{}'", line))?;
-        let expected = format!("Register {PREFIX}");
-        if reg != expected {
-            return Err(format!("Expected: {}, found: {}", expected, reg));
-        }
-
-        val.trim()
-            .parse()
-            .map_err(|_| format!("could not parse '{}' as string", val))
+    fn parse_register<const PREFIX: char>(line: Span<'_>) -> Result<usize, ParseError> {
+        line.field(&format!("Register {PREFIX}"), ':')?.unsigned()
     }
 
     fn execute(&mut self) -> Vec<u8> {
@@ -187,7 +205,7 @@ pub fn part_one(input: &str) -> Option<String> {
     Some(output.into_iter().map(|n| n.to_string()).join(","))
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(_input: &str) -> Option<u32> {
     None
 }
 
@@ -195,6 +213,24 @@ pub fn part_two(input: &str) -> Option<u32> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate() {
+        let mut validator =
+            Validator::new("Register A: 729\nRegister B: x\n\nProgram: 0,7,8,1,3\n");
+        validate(&mut validator);
+        let messages: Vec<String> = validator.finish().iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "error: line 1, column 1: missing register C",
+                "error: line 2, column 13: expected unsigned integer, found 'x'",
+                "error: line 4, column 1: expected pairs of opcodes and operands, found 5 numbers",
+                "error: line 4, column 12: combo operand 7 is reserved",
+                "error: line 4, column 14: expected a value in 0..=7, found 8",
+            ]
+        );
+    }
+
     #[test]
     fn test_example_1() {
         let mut computer = Computer::new(vec![2, 6], 0, 0, 9);
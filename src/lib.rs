@@ -1,3 +1,7 @@
+pub mod math;
+pub mod parse;
+pub mod property;
+pub mod search;
 pub mod template;
 
 // Use this file to add helper functions and additional modules.
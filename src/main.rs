@@ -1,14 +1,27 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, check, dashboard, download, read, scaffold, solve, stars, time,
+};
 use args::{parse, AppArguments};
 
+#[cfg(feature = "today")]
+use advent_of_code::template::commands::scaffold::Overwrite;
 #[cfg(feature = "today")]
 use advent_of_code::template::Day;
 #[cfg(feature = "today")]
 use std::process;
 
 mod args {
+    use advent_of_code::template::alloc::AllocationCounting;
+    use advent_of_code::template::commands::scaffold::Overwrite;
+    use advent_of_code::template::input::InputSource;
     use advent_of_code::template::Day;
-    use std::process;
+    use advent_of_code::template::{
+        parse_duration, ReportFormat, RunOptions, HISTORY_FILE_PATH, TIMINGS_FILE_PATH,
+    };
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use std::{env, process};
 
     pub enum AppArguments {
         Download {
@@ -17,62 +30,141 @@ mod args {
         Read {
             day: Day,
         },
+        Check {
+            day: Day,
+            input: InputSource,
+        },
         Scaffold {
             day: Day,
             download: bool,
-            overwrite: bool,
+            overwrite: Overwrite,
+            template: Option<String>,
+            dry_run: bool,
         },
         Solve {
             day: Day,
             release: bool,
-            dhat: bool,
+            allocations: AllocationCounting,
             submit: Option<u8>,
+            params: Vec<String>,
+            input: InputSource,
         },
         All {
             release: bool,
+            options: RunOptions,
+            format: ReportFormat,
         },
         Time {
             all: bool,
             day: Option<Day>,
             store: bool,
+            compare: Option<f64>,
+            options: RunOptions,
+            format: ReportFormat,
+            trend: bool,
+            timings: PathBuf,
+            history: PathBuf,
+        },
+        Stars {
+            timings: PathBuf,
+        },
+        Dashboard {
+            timings: PathBuf,
         },
         #[cfg(feature = "today")]
         Today,
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
-        let mut args = pico_args::Arguments::from_env();
+        // `--example` takes an optional value, which pico-args can't express,
+        // so input selection is pulled out of the arguments up front.
+        let mut raw_args: Vec<String> = env::args().skip(1).collect();
+        let input = InputSource::extract(&mut raw_args)?;
+        let mut args =
+            pico_args::Arguments::from_vec(raw_args.into_iter().map(OsString::from).collect());
 
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
+                options: RunOptions {
+                    timeout: args.opt_value_from_fn("--timeout", duration)?,
+                    budget: args.opt_value_from_fn("--budget", duration)?,
+                    jobs: args.opt_value_from_str(["-j", "--jobs"])?,
+                    ..RunOptions::default()
+                },
+                format: args.opt_value_from_str("--format")?.unwrap_or_default(),
             },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let compare = args.contains("--compare");
+                let threshold: f64 = args.opt_value_from_str("--threshold")?.unwrap_or(10.0);
+                let trend = args.contains("--trend");
+                let timings = args
+                    .opt_value_from_str("--timings")?
+                    .unwrap_or_else(|| PathBuf::from(TIMINGS_FILE_PATH));
+                let history = args
+                    .opt_value_from_str("--history")?
+                    .unwrap_or_else(|| PathBuf::from(HISTORY_FILE_PATH));
+                let timeout = args.opt_value_from_fn("--timeout", duration)?;
+                let budget = args.opt_value_from_fn("--budget", duration)?;
+                let format = args.opt_value_from_str("--format")?.unwrap_or_default();
 
                 AppArguments::Time {
                     all,
                     day: args.opt_free_from_str()?,
                     store,
+                    compare: compare.then_some(threshold / 100.0),
+                    options: RunOptions {
+                        allocations: allocation_counting(&mut args)?,
+                        counters: args.contains("--counters"),
+                        timeout,
+                        budget,
+                        jobs: None,
+                    },
+                    format,
+                    trend,
+                    timings,
+                    history,
                 }
             }
+            Some("stars") => AppArguments::Stars {
+                timings: args
+                    .opt_value_from_str("--timings")?
+                    .unwrap_or_else(|| PathBuf::from(TIMINGS_FILE_PATH)),
+            },
+            Some("dashboard") => AppArguments::Dashboard {
+                timings: args
+                    .opt_value_from_str("--timings")?
+                    .unwrap_or_else(|| PathBuf::from(TIMINGS_FILE_PATH)),
+            },
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
             },
             Some("read") => AppArguments::Read {
                 day: args.free_from_str()?,
             },
+            Some("check") => AppArguments::Check {
+                day: args.free_from_str()?,
+                input: input.clone(),
+            },
             Some("scaffold") => AppArguments::Scaffold {
                 day: args.free_from_str()?,
                 download: args.contains("--download"),
-                overwrite: args.contains("--overwrite"),
+                overwrite: Overwrite {
+                    module: args.contains("--overwrite"),
+                    examples: args.contains("--overwrite-examples"),
+                },
+                template: args.opt_value_from_str("--template")?,
+                dry_run: args.contains("--dry-run"),
             },
             Some("solve") => AppArguments::Solve {
                 day: args.free_from_str()?,
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
+                allocations: allocation_counting(&mut args)?,
+                params: args.values_from_str("--param")?,
+                input: input.clone(),
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -86,6 +178,17 @@ mod args {
             }
         };
 
+        if input != InputSource::Puzzle
+            && !matches!(
+                app_args,
+                AppArguments::Solve { .. } | AppArguments::Check { .. }
+            )
+        {
+            eprintln!(
+                "Warning: `--input`, `--example` and `-` are only supported by `solve` and `check`."
+            );
+        }
+
         let remaining = args.finish();
         if !remaining.is_empty() {
             eprintln!("Warning: unknown argument(s): {remaining:?}.");
@@ -93,6 +196,29 @@ mod args {
 
         Ok(app_args)
     }
+
+    /// A time limit such as `30s` or `500ms`.
+    fn duration(value: &str) -> Result<Duration, String> {
+        parse_duration(value)
+            .filter(|nanos| *nanos > 0.0)
+            .map(
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                |nanos| Duration::from_nanos(nanos as u64),
+            )
+            .ok_or_else(|| format!("expected a duration such as `30s`, found `{value}`."))
+    }
+
+    /// `--allocs` counts allocations with the counting allocator, `--dhat` with the dhat heap profiler.
+    fn allocation_counting(
+        args: &mut pico_args::Arguments,
+    ) -> Result<AllocationCounting, Box<dyn std::error::Error>> {
+        match (args.contains("--allocs"), args.contains("--dhat")) {
+            (true, true) => Err("`--allocs` and `--dhat` can not be combined.".into()),
+            (true, false) => Ok(AllocationCounting::Counter),
+            (false, true) => Ok(AllocationCounting::Dhat),
+            (false, false) => Ok(AllocationCounting::Off),
+        }
+    }
 }
 
 fn main() {
@@ -102,32 +228,79 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
+            AppArguments::All {
+                release,
+                options,
+                format,
+            } => all::handle(release, options, format),
+            AppArguments::Time {
+                day,
+                all,
+                store,
+                compare,
+                options,
+                format,
+                trend,
+                timings,
+                history,
+            } => {
+                if trend {
+                    time::trend(day, &history);
+                } else {
+                    time::handle(
+                        day, all, store, compare, options, format, &timings, &history,
+                    );
+                }
+            }
+            AppArguments::Stars { timings } => stars::handle(&timings),
+            AppArguments::Dashboard { timings } => dashboard::handle(&timings),
+            AppArguments::Download { day } => {
+                if let Err(e) = download::handle(day) {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            }
             AppArguments::Read { day } => read::handle(day),
+            AppArguments::Check { day, input } => check::handle(day, &input),
             AppArguments::Scaffold {
                 day,
                 download,
                 overwrite,
+                template,
+                dry_run,
             } => {
-                scaffold::handle(day, overwrite);
-                if download {
-                    download::handle(day);
+                // download first, so the examples can be taken from the puzzle description.
+                if download && !dry_run {
+                    if let Err(e) = download::handle(day) {
+                        eprintln!("Warning: {e}");
+                        eprintln!("Scaffolding without examples.");
+                    }
+                }
+                if let Err(e) = scaffold::handle(day, overwrite, template.as_deref(), dry_run) {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
                 }
             }
             AppArguments::Solve {
                 day,
                 release,
-                dhat,
+                allocations,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                params,
+                input,
+            } => solve::handle(day, release, allocations, submit, &params, &input),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
                     Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
+                        if let Err(e) = download::handle(day) {
+                            eprintln!("Warning: {e}");
+                            eprintln!("Scaffolding without examples.");
+                        }
+                        if let Err(e) = scaffold::handle(day, Overwrite::default(), None, false) {
+                            eprintln!("Error: {e}");
+                            process::exit(1);
+                        }
                         read::handle(day)
                     }
                     None => {
@@ -0,0 +1,304 @@
+//! Number theory and digit helpers, generic over the primitive integer types.
+
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+/// Operations shared by every primitive integer type.
+/// The `checked_*` methods return `None` on overflow, like their inherent counterparts.
+pub trait Integer:
+    Copy
+    + Ord
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TEN: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_pow(self, exp: u32) -> Option<Self>;
+
+    /// The non-negative remainder of `self / modulus`.
+    fn rem_euclid(self, modulus: Self) -> Self;
+
+    /// Absolute value, or `None` for the minimum of a signed type; a no-op for unsigned types.
+    fn checked_abs(self) -> Option<Self>;
+}
+
+/// Marker for the integer types that can represent negative values.
+pub trait SignedInteger: Integer {}
+
+macro_rules! impl_integer {
+    (@impl $t:ty, |$n:ident| $abs:expr) => {
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const TEN: Self = 10;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn checked_pow(self, exp: u32) -> Option<Self> {
+                <$t>::checked_pow(self, exp)
+            }
+
+            fn rem_euclid(self, modulus: Self) -> Self {
+                <$t>::rem_euclid(self, modulus)
+            }
+
+            fn checked_abs(self) -> Option<Self> {
+                let $n = self;
+                $abs
+            }
+        }
+    };
+    (unsigned: $($t:ty),*) => {$(
+        impl_integer!(@impl $t, |n| Some(n));
+    )*};
+    (signed: $($t:ty),*) => {$(
+        impl_integer!(@impl $t, |n| n.checked_abs());
+        impl SignedInteger for $t {}
+    )*};
+}
+
+impl_integer!(unsigned: u8, u16, u32, u64, u128, usize);
+impl_integer!(signed: i8, i16, i32, i64, i128, isize);
+
+/// Greatest common divisor, always non-negative. `gcd(0, 0)` is 0.
+///
+/// Panics if the result doesn't fit in `T`, i.e. for `gcd(T::MIN, 0)` and `gcd(T::MIN, T::MIN)`.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    // the minimum of a signed type has no absolute value, so it is reduced by the other one first.
+    let (mut a, mut b) = match (a.checked_abs(), b.checked_abs()) {
+        (Some(a), Some(b)) => (a, b),
+        (None, Some(b)) if b != T::ZERO => (b, a.rem_euclid(b)),
+        (Some(a), None) if a != T::ZERO => (a, b.rem_euclid(a)),
+        _ => panic!("gcd({a}, {b}) overflows"),
+    };
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or `None` if it overflows. `lcm(0, n)` is 0.
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a.checked_abs()? / gcd(a, b)).checked_mul(b.checked_abs()?)
+}
+
+/// Extended Euclidean algorithm: returns `(g, x, y)` such that `a * x + b * y == g == gcd(a, b)`.
+pub fn extended_gcd<T: SignedInteger>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+
+    while r != T::ZERO {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < T::ZERO {
+        (T::ZERO - old_r, T::ZERO - old_x, T::ZERO - old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `modulus`, if `a` and `modulus` are coprime.
+pub fn mod_inverse<T: SignedInteger>(a: T, modulus: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (g == T::ONE).then(|| x.rem_euclid(modulus))
+}
+
+/// `base ^ exp % modulus` by repeated squaring, or `None` if an intermediate product overflows.
+pub fn mod_pow<T: Integer>(base: T, mut exp: u64, modulus: T) -> Option<T> {
+    let mut result = T::ONE.rem_euclid(modulus);
+    let mut base = base.rem_euclid(modulus);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result.checked_mul(base)?.rem_euclid(modulus);
+        }
+        base = base.checked_mul(base)?.rem_euclid(modulus);
+        exp >>= 1;
+    }
+    Some(result)
+}
+
+/// Chinese remainder theorem: solves `x ≡ residue (mod modulus)` for all `(residue, modulus)` pairs.
+///
+/// Moduli need not be coprime. Returns the smallest non-negative solution together with the
+/// combined modulus (the lcm of all moduli), or `None` if a modulus isn't positive, the
+/// congruences contradict each other or the computation overflows.
+pub fn crt<T: SignedInteger>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let mut result = T::ZERO;
+    let mut modulus = T::ONE;
+
+    for &(residue, m) in congruences {
+        if m <= T::ZERO {
+            return None;
+        }
+        let (g, p, _) = extended_gcd(modulus, m);
+        let difference = residue.checked_sub(result)?;
+        if difference % g != T::ZERO {
+            return None;
+        }
+
+        let m_over_g = m / g;
+        // solve `result + modulus * k ≡ residue (mod m)` for k.
+        let k = (difference / g)
+            .rem_euclid(m_over_g)
+            .checked_mul(p.rem_euclid(m_over_g))?
+            .rem_euclid(m_over_g);
+
+        let combined = modulus.checked_mul(m_over_g)?;
+        result = result
+            .checked_add(modulus.checked_mul(k)?)?
+            .rem_euclid(combined);
+        modulus = combined;
+    }
+
+    Some((result, modulus))
+}
+
+/// Decimal digit operations on unsigned integers.
+pub trait Digits: Integer {
+    /// Number of decimal digits, where 0 has a single digit.
+    fn digit_count(self) -> u32;
+
+    /// Splits off the last `n` digits: `1234.split_digits(1) == (123, 4)`.
+    fn split_digits(self, n: u32) -> (Self, Self) {
+        match Self::TEN.checked_pow(n) {
+            Some(divisor) => (self / divisor, self % divisor),
+            None => (Self::ZERO, self),
+        }
+    }
+
+    /// Splits a number with an even number of digits into its halves: `1234 -> (12, 34)`.
+    fn split_in_half(self) -> Option<(Self, Self)> {
+        let count = self.digit_count();
+        count
+            .is_multiple_of(2)
+            .then(|| self.split_digits(count / 2))
+    }
+
+    /// Appends the digits of `other`: `12.concat(345) == Some(12345)`, or `None` on overflow.
+    fn concat(self, other: Self) -> Option<Self> {
+        self.checked_mul(Self::TEN.checked_pow(other.digit_count())?)?
+            .checked_add(other)
+    }
+}
+
+macro_rules! impl_digits {
+    ($($t:ty),*) => {$(
+        impl Digits for $t {
+            fn digit_count(self) -> u32 {
+                self.checked_ilog10().unwrap_or(0) + 1
+            }
+        }
+    )*};
+}
+
+impl_digits!(u8, u16, u32, u64, u128, usize);
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{crt, extended_gcd, gcd, lcm, mod_inverse, mod_pow, Digits};
+
+    #[test]
+    fn computes_gcd_and_lcm() {
+        assert_eq!(gcd(12_u32, 18), 6);
+        assert_eq!(gcd(-12_i64, 18), 6);
+        assert_eq!(gcd(0_u8, 7), 7);
+        assert_eq!(gcd(0_u8, 0), 0);
+        assert_eq!(lcm(4_u64, 6), Some(12));
+        assert_eq!(lcm(0_i32, 6), Some(0));
+        assert_eq!(lcm(200_u8, 3), None);
+    }
+
+    #[test]
+    fn computes_gcd_of_signed_minimum() {
+        assert_eq!(gcd(i64::MIN, 6), 2);
+        assert_eq!(gcd(-3_i8, i8::MIN), 1);
+        assert_eq!(gcd(i32::MIN, -1), 1);
+        assert_eq!(lcm(i64::MIN, 2), None);
+        assert_eq!(lcm(i16::MIN, i16::MIN), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "gcd(-128, 0) overflows")]
+    fn panics_if_gcd_overflows() {
+        gcd(i8::MIN, 0);
+    }
+
+    #[test]
+    fn computes_extended_gcd() {
+        for (a, b) in [(240_i64, 46), (-7, 3), (101, 103), (0, 5)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn computes_modular_inverse_and_power() {
+        assert_eq!(mod_inverse(3_i64, 11), Some(4));
+        assert_eq!(mod_inverse(-3_i64, 11), Some(7));
+        assert_eq!(mod_inverse(6_i32, 9), None);
+        assert_eq!(mod_pow(4_u64, 13, 497), Some(445));
+        assert_eq!(mod_pow(2_u8, 10, 1), Some(0));
+    }
+
+    #[test]
+    fn solves_chinese_remainder_theorem() {
+        assert_eq!(crt(&[(2_i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // non-coprime moduli.
+        assert_eq!(crt(&[(2_i64, 6), (8, 10)]), Some((8, 30)));
+        assert_eq!(crt(&[(1_i64, 6), (2, 4)]), None);
+        // per-axis periods of a 101x103 grid.
+        assert_eq!(crt(&[(12_i32, 101), (67, 103)]), Some((2436, 10403)));
+        assert_eq!(crt::<i64>(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(1_i8, 100), (2, 99)]), None);
+        // moduli must be positive.
+        assert_eq!(crt(&[(1_i64, 0)]), None);
+        assert_eq!(crt(&[(2_i64, 3), (1, -5)]), None);
+    }
+
+    #[test]
+    fn handles_digits() {
+        assert_eq!(0_u32.digit_count(), 1);
+        assert_eq!(9_u32.digit_count(), 1);
+        assert_eq!(10_u64.digit_count(), 2);
+        assert_eq!(u64::MAX.digit_count(), 20);
+        assert_eq!(1234_u32.split_digits(1), (123, 4));
+        assert_eq!(1234_u32.split_digits(10), (0, 1234));
+        assert_eq!(1000_usize.split_in_half(), Some((10, 0)));
+        assert_eq!(253000_u64.split_in_half(), Some((253, 0)));
+        assert_eq!(123_usize.split_in_half(), None);
+        assert_eq!(12_u64.concat(345), Some(12345));
+        assert_eq!(12_u64.concat(0), Some(120));
+        assert_eq!(25_u8.concat(6), None);
+    }
+}
@@ -0,0 +1,418 @@
+//! Zero-copy parsing combinators for puzzle inputs.
+//!
+//! Every value handed out is a [`Span`]: a slice of the original input that remembers where it
+//! starts, so errors can point at the offending line and column.
+
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// An error which can be returned when parsing puzzle input, pointing at a 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+/// Allows `?` on parse results inside solutions that use `String` errors.
+impl From<ParseError> for String {
+    fn from(value: ParseError) -> Self {
+        value.to_string()
+    }
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// A slice of the input together with its position in the original text.
+///
+/// Only the byte offset is kept while slicing, the line and column are counted when asked for,
+/// e.g. to build a [`ParseError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span<'a> {
+    text: &'a str,
+    /// The whole input this span was cut from.
+    source: &'a str,
+    offset: usize,
+}
+
+impl<'a> Span<'a> {
+    /// Creates a span covering the whole input, starting at line 1, column 1.
+    #[must_use]
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            source: text,
+            offset: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    #[must_use]
+    pub fn line(&self) -> usize {
+        self.position().0
+    }
+
+    #[must_use]
+    pub fn column(&self) -> usize {
+        self.position().1
+    }
+
+    /// 1-based line and column of the start of this span, counted from the start of the input.
+    fn position(&self) -> (usize, usize) {
+        let prefix = &self.source[..self.offset];
+        let line_start = prefix.rfind('\n').map_or(0, |index| index + 1);
+        (
+            1 + prefix.matches('\n').count(),
+            1 + prefix[line_start..].chars().count(),
+        )
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Creates an error located at the start of this span.
+    #[must_use]
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        let (line, column) = self.position();
+        ParseError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Sub-span for a byte range of this span.
+    fn slice(&self, start: usize, end: usize) -> Self {
+        Self {
+            text: &self.text[start..end],
+            source: self.source,
+            offset: self.offset + start,
+        }
+    }
+
+    /// Strips leading and trailing whitespace, keeping the position accurate.
+    #[must_use]
+    pub fn trim(&self) -> Self {
+        let start = self.text.len() - self.text.trim_start().len();
+        let end = self.text.trim_end().len().max(start);
+        self.slice(start, end)
+    }
+
+    /// Splits the span on the first occurrence of `separator`.
+    pub fn split_once(&self, separator: char) -> ParseResult<(Self, Self)> {
+        let index = self
+            .text
+            .find(separator)
+            .ok_or_else(|| self.error(format!("expected '{separator}' in '{}'", self.text)))?;
+        Ok((
+            self.slice(0, index),
+            self.slice(index + separator.len_utf8(), self.text.len()),
+        ))
+    }
+
+    /// Splits the span on every occurrence of `separator`.
+    pub fn split(&self, separator: char) -> impl Iterator<Item = Span<'a>> + use<'a> {
+        let span = *self;
+        let mut start = Some(0);
+        std::iter::from_fn(move || {
+            let from = start?;
+            match span.text[from..].find(separator) {
+                Some(index) => {
+                    start = Some(from + index + separator.len_utf8());
+                    Some(span.slice(from, from + index))
+                }
+                None => {
+                    start = None;
+                    Some(span.slice(from, span.text.len()))
+                }
+            }
+        })
+    }
+
+    /// Iterates the lines of this span, without their line endings.
+    pub fn lines(&self) -> impl Iterator<Item = Span<'a>> + use<'a> {
+        let span = *self;
+        let mut start = 0;
+        std::iter::from_fn(move || {
+            if start >= span.text.len() {
+                return None;
+            }
+            let end = span.text[start..]
+                .find('\n')
+                .map_or(span.text.len(), |index| start + index);
+            let line = span.slice(
+                start,
+                span.text[start..end].trim_end_matches('\r').len() + start,
+            );
+            start = end + 1;
+            Some(line)
+        })
+    }
+
+    /// Iterates the blank-line separated sections of this span.
+    pub fn sections(&self) -> impl Iterator<Item = Span<'a>> + use<'a> {
+        let span = *self;
+        let mut lines = self.lines().peekable();
+        std::iter::from_fn(move || {
+            while lines.next_if(|line| line.trim().is_empty()).is_some() {}
+            let first = lines.next()?;
+            let mut last = first;
+            while let Some(line) = lines.next_if(|line| !line.trim().is_empty()) {
+                last = line;
+            }
+            let start = first.text.as_ptr() as usize - span.text.as_ptr() as usize;
+            let end = last.text.as_ptr() as usize - span.text.as_ptr() as usize + last.text.len();
+            Some(span.slice(start, end))
+        })
+    }
+
+    /// Splits the span into exactly `N` blank-line separated sections.
+    pub fn sections_exact<const N: usize>(&self) -> ParseResult<[Span<'a>; N]> {
+        let sections: Vec<_> = self.sections().collect();
+        sections.try_into().map_err(|sections: Vec<_>| {
+            self.error(format!(
+                "expected {N} blank-line separated sections, found {}",
+                sections.len()
+            ))
+        })
+    }
+
+    /// Parses the trimmed span as an unsigned integer, rejecting any sign.
+    pub fn unsigned<T: FromStr>(self) -> ParseResult<T> {
+        let span = self.trim();
+        if span.text.is_empty() || !span.text.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(span.error(format!("expected unsigned integer, found '{}'", span.text)));
+        }
+        span.text
+            .parse()
+            .map_err(|_| span.error(format!("integer out of range: '{}'", span.text)))
+    }
+
+    /// Parses the trimmed span as an integer with an optional leading sign.
+    pub fn signed<T: FromStr>(self) -> ParseResult<T> {
+        let span = self.trim();
+        let digits = span.text.strip_prefix(['-', '+']).unwrap_or(span.text);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(span.error(format!("expected integer, found '{}'", span.text)));
+        }
+        span.text
+            .parse()
+            .map_err(|_| span.error(format!("integer out of range: '{}'", span.text)))
+    }
+
+    /// Parses every `separator` delimited element of the span with `element`.
+    pub fn list<T>(
+        &self,
+        separator: char,
+        element: impl Fn(Span<'a>) -> ParseResult<T>,
+    ) -> ParseResult<Vec<T>> {
+        self.split(separator).map(element).collect()
+    }
+
+    /// Parses every whitespace delimited element of the span with `element`.
+    pub fn words<T>(&self, element: impl Fn(Span<'a>) -> ParseResult<T>) -> ParseResult<Vec<T>> {
+        let span = *self;
+        span.text
+            .split_whitespace()
+            .map(|word| {
+                let start = word.as_ptr() as usize - span.text.as_ptr() as usize;
+                element(span.slice(start, start + word.len()))
+            })
+            .collect()
+    }
+
+    /// Expects `key`, followed by `separator` and returns the trimmed value,
+    /// e.g. `p=0,4` with `('p', '=')` or `Register A: 729` with `("Register A", ':')`.
+    pub fn field(&self, key: &str, separator: char) -> ParseResult<Span<'a>> {
+        let (actual, value) = self.split_once(separator)?;
+        if actual.trim().text != key {
+            return Err(actual.error(format!("expected '{key}', found '{}'", actual.text)));
+        }
+        Ok(value.trim())
+    }
+
+    /// Parses every line of the span into a row using `cell`, requiring all rows to be equally long.
+    pub fn grid<T>(&self, cell: impl Fn(char) -> Option<T>) -> ParseResult<Vec<Vec<T>>> {
+        let mut rows: Vec<Vec<T>> = Vec::new();
+        for line in self.lines() {
+            let row = line
+                .text
+                .char_indices()
+                .map(|(index, c)| {
+                    cell(c).ok_or_else(|| {
+                        line.slice(index, index + c.len_utf8())
+                            .error(format!("unexpected character '{c}'"))
+                    })
+                })
+                .collect::<ParseResult<Vec<T>>>()?;
+
+            if let Some(first) = rows.first() {
+                if first.len() != row.len() {
+                    return Err(line.error(format!(
+                        "expected row of length {}, found {}",
+                        first.len(),
+                        row.len()
+                    )));
+                }
+            }
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Parses a rectangular grid of decimal digits.
+    pub fn digit_grid(&self) -> ParseResult<Vec<Vec<u8>>> {
+        self.grid(|c| c.to_digit(10).map(|d| d as u8))
+    }
+}
+
+impl<'a> From<&'a str> for Span<'a> {
+    fn from(value: &'a str) -> Self {
+        Span::new(value)
+    }
+}
+
+impl Display for Span<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.text)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{ParseError, Span};
+
+    #[test]
+    fn parses_integers() {
+        assert_eq!(Span::new(" 42 ").unsigned::<u32>(), Ok(42));
+        assert_eq!(Span::new("-42").signed::<i64>(), Ok(-42));
+        assert_eq!(Span::new("+7").signed::<i8>(), Ok(7));
+        assert!(Span::new("-42").unsigned::<u32>().is_err());
+        assert!(Span::new("300").unsigned::<u8>().is_err());
+        assert!(Span::new("").signed::<i32>().is_err());
+    }
+
+    #[test]
+    fn parses_separated_lists() {
+        let values: Vec<u32> = Span::new("75,47,61").list(',', Span::unsigned).unwrap();
+        assert_eq!(values, [75, 47, 61]);
+
+        let values: Vec<i64> = Span::new("7 6  4 -2").words(Span::signed).unwrap();
+        assert_eq!(values, [7, 6, 4, -2]);
+    }
+
+    #[test]
+    fn parses_fields_of_a_line() {
+        let input = Span::new("p=0,4 v=3,-3");
+        let (position, velocity) = input.split_once(' ').unwrap();
+        let position: Vec<u32> = position
+            .field("p", '=')
+            .unwrap()
+            .list(',', Span::unsigned)
+            .unwrap();
+        let velocity: Vec<i32> = velocity
+            .field("v", '=')
+            .unwrap()
+            .list(',', Span::signed)
+            .unwrap();
+        assert_eq!(position, [0, 4]);
+        assert_eq!(velocity, [3, -3]);
+    }
+
+    #[test]
+    fn parses_fields() {
+        let register: usize = Span::new("Register A: 729")
+            .field("Register A", ':')
+            .unwrap()
+            .unsigned()
+            .unwrap();
+        assert_eq!(register, 729);
+
+        assert_eq!(
+            Span::new("Register B: 0").field("Register A", ':'),
+            Err(ParseError {
+                line: 1,
+                column: 1,
+                message: "expected 'Register A', found 'Register B'".into()
+            })
+        );
+    }
+
+    #[test]
+    fn splits_sections() {
+        let input = Span::new("47|53\n97|13\n\n75,47\n\n\n61,13\n");
+        let sections: Vec<_> = input.sections().collect();
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0].as_str(), "47|53\n97|13");
+        assert_eq!(sections[1].as_str(), "75,47");
+        assert_eq!((sections[2].line(), sections[2].column()), (7, 1));
+
+        assert!(input.sections_exact::<2>().is_err());
+        assert!(Span::new("a\n\nb").sections_exact::<2>().is_ok());
+    }
+
+    #[test]
+    fn parses_digit_grids() {
+        let grid = Span::new("012\n345\n").digit_grid().unwrap();
+        assert_eq!(grid, vec![vec![0, 1, 2], vec![3, 4, 5]]);
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        let err = Span::new("012\n3x5").digit_grid().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+
+        let err = Span::new("012\n34").digit_grid().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+
+        let err = Span::new("1,2\n3,y")
+            .lines()
+            .map(|line| line.list(',', Span::unsigned::<u32>))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3: expected unsigned integer, found 'y'"
+        );
+    }
+
+    #[test]
+    fn locates_nested_spans() {
+        let line = Span::new("é\n  p=1,ü").lines().nth(1).unwrap();
+        let values = line.field("p", '=').unwrap();
+        assert_eq!((values.line(), values.column()), (2, 5));
+
+        let err = values.list(',', Span::unsigned::<u32>).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 7));
+    }
+
+    #[test]
+    fn handles_crlf_line_endings() {
+        let lines: Vec<_> = Span::new("ab\r\ncd\r\n")
+            .lines()
+            .map(|l| l.as_str())
+            .collect();
+        assert_eq!(lines, ["ab", "cd"]);
+    }
+}
@@ -0,0 +1,395 @@
+//! Property-based testing for solutions.
+//!
+//! A generator draws random choices from a [`Gen`] to build a value, e.g. a puzzle input. When a
+//! property fails, the recorded choices are shrunk (dropped or lowered) and the generator
+//! is replayed on them, so every shrunk value is still one the generator can produce.
+//!
+//! Runs use a fixed seed, so a failure reproduces on every run. Set `AOC_SEED` to another seed, or
+//! to `random` for a fresh one that is printed on failure, and `AOC_CASES` to change the number of
+//! cases.
+
+use std::env;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEFAULT_CASES: usize = 200;
+const DEFAULT_SEED: u64 = 2024;
+/// Upper bound on the number of replays while shrinking a failing case.
+const MAX_SHRINK_ATTEMPTS: usize = 5_000;
+
+/// Source of random choices handed to generators.
+pub struct Gen {
+    state: u64,
+    /// Choices to replay instead of drawing random ones, `0` once exhausted.
+    replay: Option<Vec<u64>>,
+    choices: Vec<u64>,
+}
+
+impl Gen {
+    fn random(seed: u64) -> Self {
+        Self {
+            state: seed,
+            replay: None,
+            choices: vec![],
+        }
+    }
+
+    fn replaying(choices: Vec<u64>) -> Self {
+        Self {
+            state: 0,
+            replay: Some(choices),
+            choices: vec![],
+        }
+    }
+
+    /// Next output of a splitmix64 generator.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A choice in `0..=max`. Smaller choices are simpler, shrinking lowers them towards `0`.
+    fn choice(&mut self, max: u64) -> u64 {
+        let choice = match &self.replay {
+            Some(replay) => replay
+                .get(self.choices.len())
+                .copied()
+                .unwrap_or(0)
+                .min(max),
+            None => match max.checked_add(1) {
+                Some(options) => self.next_u64() % options,
+                None => self.next_u64(),
+            },
+        };
+        self.choices.push(choice);
+        choice
+    }
+
+    pub fn u64(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range {start}..={end}");
+        start + self.choice(end - start)
+    }
+
+    pub fn u32(&mut self, range: RangeInclusive<u32>) -> u32 {
+        let (start, end) = range.into_inner();
+        self.u64(u64::from(start)..=u64::from(end)) as u32
+    }
+
+    pub fn usize(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        self.u64(start as u64..=end as u64) as usize
+    }
+
+    pub fn i64(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range {start}..={end}");
+        start.wrapping_add(self.choice(end.abs_diff(start)) as i64)
+    }
+
+    /// `true` with a probability of `1 / n`, shrinks to `false`.
+    pub fn one_in(&mut self, n: u64) -> bool {
+        assert!(n > 0, "one_in(0) has no probability to draw with");
+        self.choice(n.saturating_sub(1)) == n.saturating_sub(1)
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        assert!(!items.is_empty(), "can not choose from an empty slice");
+        &items[self.usize(0..=items.len() - 1)]
+    }
+
+    /// Shuffles `items` in place, shrinks to their original order.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = i - self.usize(0..=i);
+            items.swap(i, j);
+        }
+    }
+
+    /// A vector of about the average length of `len`, shrinks by dropping elements.
+    pub fn vec<T>(
+        &mut self,
+        len: RangeInclusive<usize>,
+        mut element: impl FnMut(&mut Gen) -> T,
+    ) -> Vec<T> {
+        let (min, max) = len.into_inner();
+        let average = ((max.saturating_sub(min)) / 2 + 1) as u64;
+
+        // every optional element is preceded by a choice to stop, so shrinking can drop the
+        // choices of an element without shifting the ones of the elements after it.
+        let mut items = vec![];
+        while items.len() < max && (items.len() < min || self.choice(average) != 0) {
+            items.push(element(self));
+        }
+        items
+    }
+}
+
+/// A failing case, after shrinking.
+#[derive(Debug)]
+pub struct Counterexample<T> {
+    pub value: T,
+    /// The case that failed first, counting from `1`.
+    pub case: usize,
+    /// How many times the value was shrunk.
+    pub shrinks: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Config {
+    pub cases: usize,
+    pub seed: u64,
+}
+
+impl Default for Config {
+    /// Reads `AOC_CASES` and `AOC_SEED`, where a seed of `random` is based on the current time.
+    fn default() -> Self {
+        let cases = env::var("AOC_CASES")
+            .ok()
+            .and_then(|cases| cases.parse().ok())
+            .unwrap_or(DEFAULT_CASES);
+        let seed = match env::var("AOC_SEED").ok().as_deref() {
+            None => DEFAULT_SEED,
+            Some("random") => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_nanos() as u64),
+            Some(seed) => seed.parse().unwrap_or_else(|_| {
+                panic!("expected AOC_SEED to be a number or `random`, found `{seed}`")
+            }),
+        };
+        Self { cases, seed }
+    }
+}
+
+/// Runs `property` on `config.cases` generated values and shrinks the first one it fails for.
+pub fn find_counterexample<T>(
+    config: Config,
+    generate: impl Fn(&mut Gen) -> T,
+    property: impl Fn(&T) -> bool,
+) -> Option<Counterexample<T>> {
+    let mut g = Gen::random(config.seed);
+
+    for case in 1..=config.cases {
+        g.choices.clear();
+        let value = generate(&mut g);
+        if !property(&value) {
+            let (value, shrinks) = shrink(value, g.choices.clone(), &generate, &property);
+            return Some(Counterexample {
+                value,
+                case,
+                shrinks,
+            });
+        }
+    }
+
+    None
+}
+
+/// Replays the generator on simpler choices for as long as the property keeps failing.
+fn shrink<T>(
+    mut value: T,
+    mut choices: Vec<u64>,
+    generate: &impl Fn(&mut Gen) -> T,
+    property: &impl Fn(&T) -> bool,
+) -> (T, usize) {
+    let mut shrinks = 0;
+    let mut attempts = 0;
+
+    // replays `candidate` and keeps it if it still fails and is simpler than the current choices.
+    let mut attempt = |candidate: Vec<u64>, value: &mut T, choices: &mut Vec<u64>| {
+        if attempts >= MAX_SHRINK_ATTEMPTS {
+            return false;
+        }
+        attempts += 1;
+
+        let mut g = Gen::replaying(candidate);
+        let shrunk = generate(&mut g);
+        let is_simpler = (g.choices.len(), &g.choices) < (choices.len(), &*choices);
+        if is_simpler && !property(&shrunk) {
+            *value = shrunk;
+            *choices = g.choices;
+            shrinks += 1;
+            true
+        } else {
+            false
+        }
+    };
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+
+        // drop runs of choices, e.g. an element of a vector along with the choices it consists of.
+        for size in (1..=8).rev() {
+            let mut start = 0;
+            while start + size <= choices.len() {
+                let mut candidate = choices.clone();
+                candidate.drain(start..start + size);
+                if attempt(candidate, &mut value, &mut choices) {
+                    improved = true;
+                } else {
+                    start += 1;
+                }
+            }
+        }
+
+        // lower single choices, searching for the smallest one that still fails.
+        let mut i = 0;
+        while i < choices.len() {
+            let (mut low, mut high) = (0, choices[i]);
+            // lowering a choice can end the value early, e.g. a shorter vector.
+            while low < high && i < choices.len() {
+                let middle = low + (high - low) / 2;
+                let mut candidate = choices.clone();
+                candidate[i] = middle;
+                if attempt(candidate, &mut value, &mut choices) {
+                    improved = true;
+                    high = middle;
+                } else {
+                    low = middle + 1;
+                }
+            }
+            i += 1;
+        }
+    }
+
+    (value, shrinks)
+}
+
+/// Asserts that `property` holds for generated values, panicking with the shrunk counterexample.
+pub fn check<T: Debug>(generate: impl Fn(&mut Gen) -> T, property: impl Fn(&T) -> bool) {
+    let config = Config::default();
+    if let Some(counterexample) = find_counterexample(config, generate, property) {
+        panic!(
+            "property failed at case {} (shrunk {} times), rerun with AOC_SEED={}:\n{:#?}",
+            counterexample.case, counterexample.shrinks, config.seed, counterexample.value
+        );
+    }
+}
+
+/// Asserts that a fast implementation agrees with a simple one, e.g. a brute-force solution.
+pub fn differential<T: Debug, R: PartialEq + Debug>(
+    generate: impl Fn(&mut Gen) -> T,
+    fast: impl Fn(&T) -> R,
+    simple: impl Fn(&T) -> R,
+) {
+    let config = Config::default();
+    if let Some(counterexample) =
+        find_counterexample(config, generate, |value| fast(value) == simple(value))
+    {
+        let value = counterexample.value;
+        panic!(
+            "implementations disagree at case {} (shrunk {} times), rerun with AOC_SEED={}:\n{:#?}\n  fast: {:?}\nsimple: {:?}",
+            counterexample.case,
+            counterexample.shrinks,
+            config.seed,
+            value,
+            fast(&value),
+            simple(&value)
+        );
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{differential, find_counterexample, Config, Gen};
+
+    const CONFIG: Config = Config {
+        cases: 500,
+        seed: 2024,
+    };
+
+    #[test]
+    fn draws_within_ranges() {
+        let mut g = Gen::random(7);
+        for _ in 0..1000 {
+            assert!((3..=5).contains(&g.u32(3..=5)));
+            assert!((-2..=2).contains(&g.i64(-2..=2)));
+            assert!((1..=4).contains(&g.vec(1..=4, |g| g.one_in(2)).len()));
+        }
+        assert_eq!(g.u64(9..=9), 9);
+        assert!(g.i64(i64::MIN..=i64::MAX) != g.i64(i64::MIN..=i64::MAX));
+    }
+
+    #[test]
+    fn replays_choices() {
+        let mut g = Gen::replaying(vec![2, 100]);
+        assert_eq!(g.u32(10..=20), 12);
+        assert_eq!(g.u32(0..=5), 5, "choices are clamped to the range.");
+        assert_eq!(g.u32(7..=9), 7, "exhausted choices are 0.");
+
+        let mut items = [1, 2, 3, 4];
+        Gen::replaying(vec![]).shuffle(&mut items);
+        assert_eq!(items, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "can not choose from an empty slice")]
+    fn rejects_choosing_from_nothing() {
+        Gen::random(7).choose::<u32>(&[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "one_in(0)")]
+    fn rejects_zero_probability() {
+        Gen::random(7).one_in(0);
+    }
+
+    #[test]
+    fn passes_if_property_holds() {
+        let found = find_counterexample(CONFIG, |g| g.u64(0..=100), |n| *n <= 100);
+        assert!(found.is_none());
+    }
+
+    #[test]
+    fn passes_if_implementations_agree() {
+        differential(
+            |g| g.vec(0..=20, |g| g.u32(0..=1000)),
+            |v| v.iter().max().copied(),
+            |v| v.iter().copied().reduce(u32::max),
+        );
+    }
+
+    #[test]
+    fn shrinks_to_minimal_counterexample() {
+        let found = find_counterexample(
+            CONFIG,
+            |g| g.vec(0..=30, |g| g.u32(0..=1000)),
+            |v| v.iter().all(|n| *n < 500),
+        )
+        .unwrap();
+        assert_eq!(found.value, [500]);
+        assert!(found.shrinks > 0);
+    }
+
+    #[test]
+    fn shrinks_while_keeping_generated_invariants() {
+        // shuffled vectors of even length.
+        let found = find_counterexample(
+            CONFIG,
+            |g| {
+                let mut items = g.vec(0..=10, |g| [g.u32(0..=100), g.u32(0..=100)]).concat();
+                g.shuffle(&mut items);
+                items
+            },
+            |v| v.iter().filter(|n| **n > 50).count() < 3,
+        )
+        .unwrap();
+        let value = found.value;
+        assert_eq!(value.len() % 2, 0);
+        assert!(
+            value.len() <= 6,
+            "{value:?} has at most one pair without a failing element."
+        );
+        assert_eq!(
+            value.iter().filter(|n| **n > 0).collect::<Vec<_>>(),
+            [&51; 3]
+        );
+    }
+}
@@ -0,0 +1,548 @@
+//! Generic graph search algorithms.
+//!
+//! Graphs are never materialised: every algorithm takes a start state (or a set of nodes) and a
+//! neighbour function, so any `Clone + Eq + Hash` value can serve as a state.
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// A path found by a search, from the start state to the goal (both inclusive).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub states: Vec<S>,
+    pub cost: C,
+}
+
+/// Keeps every discovered state once, addressing it by index so states need not be `Ord`.
+struct Visited<S> {
+    states: Vec<S>,
+    parents: Vec<usize>,
+    indices: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash> Visited<S> {
+    fn new(start: S) -> Self {
+        Self {
+            states: vec![start.clone()],
+            parents: vec![0],
+            indices: HashMap::from([(start, 0)]),
+        }
+    }
+
+    /// Index of `state` and whether it was discovered just now.
+    fn insert(&mut self, state: S, parent: usize) -> (usize, bool) {
+        match self.indices.entry(state) {
+            Entry::Occupied(entry) => (*entry.get(), false),
+            Entry::Vacant(entry) => {
+                let index = self.states.len();
+                self.states.push(entry.key().clone());
+                self.parents.push(parent);
+                entry.insert(index);
+                (index, true)
+            }
+        }
+    }
+
+    fn path_to(&self, mut index: usize) -> Vec<S> {
+        let mut path = vec![self.states[index].clone()];
+        while index != 0 {
+            index = self.parents[index];
+            path.push(self.states[index].clone());
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Breadth-first search for the closest state satisfying `is_goal`, counting each step as 1.
+pub fn bfs<S, N, I>(
+    start: S,
+    mut neighbours: N,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = Visited::new(start);
+    let mut queue = VecDeque::from([(0, 0)]);
+
+    while let Some((index, distance)) = queue.pop_front() {
+        if is_goal(&visited.states[index]) {
+            return Some(Path {
+                states: visited.path_to(index),
+                cost: distance,
+            });
+        }
+        for next in neighbours(&visited.states[index].clone()) {
+            let (next_index, is_new) = visited.insert(next, index);
+            if is_new {
+                queue.push_back((next_index, distance + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Breadth-first search returning the distance to every state reachable from `start`.
+pub fn bfs_reachable<S, N, I>(start: S, mut neighbours: N) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((state, distance)) = queue.pop_front() {
+        for next in neighbours(&state) {
+            if let Entry::Vacant(entry) = distances.entry(next.clone()) {
+                entry.insert(distance + 1);
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    distances
+}
+
+/// Dijkstra's algorithm: the cheapest path to a state satisfying `is_goal`.
+/// `neighbours` yields the next states together with the (non-negative) cost of the step.
+pub fn dijkstra<S, C, N, I>(
+    start: S,
+    neighbours: N,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, neighbours, |_| C::default(), is_goal)
+}
+
+/// A* search: like [`dijkstra`], guided by a `heuristic` that must never overestimate the
+/// remaining cost to the goal. A state is expanded again if a cheaper way to it turns up after
+/// its expansion, so the heuristic does not need to be consistent.
+pub fn astar<S, C, N, I>(
+    start: S,
+    mut neighbours: N,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut visited = Visited::new(start);
+    let mut costs = vec![C::default()];
+    let mut closed = vec![false];
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&visited.states[0]), 0))]);
+
+    while let Some(Reverse((_, index))) = queue.pop() {
+        if closed[index] {
+            continue;
+        }
+        closed[index] = true;
+
+        let state = visited.states[index].clone();
+        let cost = costs[index];
+        if is_goal(&state) {
+            return Some(Path {
+                states: visited.path_to(index),
+                cost,
+            });
+        }
+
+        for (next, step_cost) in neighbours(&state) {
+            let next_cost = cost + step_cost;
+            let (next_index, is_new) = visited.insert(next, index);
+            if is_new {
+                costs.push(next_cost);
+                closed.push(false);
+            } else if next_cost >= costs[next_index] {
+                continue;
+            } else {
+                costs[next_index] = next_cost;
+                visited.parents[next_index] = index;
+                closed[next_index] = false;
+            }
+            let estimate = next_cost + heuristic(&visited.states[next_index]);
+            queue.push(Reverse((estimate, next_index)));
+        }
+    }
+
+    None
+}
+
+/// Orders `nodes` so that every node comes before its `successors`.
+/// Successors outside of `nodes` are ignored. Fails with a node on a cycle if there is no such order.
+pub fn topological_sort<S, N, I>(
+    nodes: impl IntoIterator<Item = S>,
+    mut successors: N,
+) -> Result<Vec<S>, S>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let nodes: Vec<S> = nodes.into_iter().collect();
+    let mut in_degrees: HashMap<S, usize> = nodes.iter().map(|n| (n.clone(), 0)).collect();
+    let mut edges: HashMap<S, Vec<S>> = HashMap::new();
+
+    for node in &nodes {
+        let targets: Vec<S> = successors(node)
+            .into_iter()
+            .filter(|s| in_degrees.contains_key(s))
+            .collect();
+        for target in &targets {
+            *in_degrees.get_mut(target).expect("is a node") += 1;
+        }
+        edges.insert(node.clone(), targets);
+    }
+
+    let mut queue: VecDeque<S> = nodes
+        .iter()
+        .filter(|n| in_degrees[*n] == 0)
+        .cloned()
+        .collect();
+    let mut sorted = Vec::with_capacity(nodes.len());
+
+    while let Some(node) = queue.pop_front() {
+        for target in &edges[&node] {
+            let degree = in_degrees.get_mut(target).expect("is a node");
+            *degree -= 1;
+            if *degree == 0 {
+                queue.push_back(target.clone());
+            }
+        }
+        sorted.push(node);
+    }
+
+    if sorted.len() == nodes.len() {
+        Ok(sorted)
+    } else {
+        Err(nodes
+            .into_iter()
+            .find(|n| in_degrees[n] > 0)
+            .expect("a node with remaining edges"))
+    }
+}
+
+/// Groups `nodes` into sets that are connected through `neighbours`, in order of first appearance.
+/// The neighbour relation is expected to be symmetric.
+pub fn connected_components<S, N, I>(
+    nodes: impl IntoIterator<Item = S>,
+    mut neighbours: N,
+) -> Vec<HashSet<S>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut seen: HashSet<S> = HashSet::new();
+    let mut components = Vec::new();
+
+    for node in nodes {
+        if seen.contains(&node) {
+            continue;
+        }
+        let component: HashSet<S> = bfs_reachable(node, &mut neighbours).into_keys().collect();
+        seen.extend(component.iter().cloned());
+        components.push(component);
+    }
+
+    components
+}
+
+/// Finds a cycle in the directed graph reachable from `start`, returned as the states along it
+/// with the first state repeated at the end.
+pub fn find_cycle<S, N, I>(start: S, mut successors: N) -> Option<Vec<S>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    // iterative DFS that keeps the current path on a stack.
+    let mut finished: HashSet<S> = HashSet::new();
+    let mut on_path: HashMap<S, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut path = vec![start.clone()];
+    let mut stack = vec![successors(&start).into_iter().collect::<Vec<_>>()];
+
+    while let Some(pending) = stack.last_mut() {
+        let Some(next) = pending.pop() else {
+            let done = path.pop().expect("path matches stack");
+            on_path.remove(&done);
+            finished.insert(done);
+            stack.pop();
+            continue;
+        };
+
+        if let Some(&index) = on_path.get(&next) {
+            let mut cycle = path[index..].to_vec();
+            cycle.push(next);
+            return Some(cycle);
+        }
+        if finished.contains(&next) {
+            continue;
+        }
+
+        on_path.insert(next.clone(), path.len());
+        stack.push(successors(&next).into_iter().collect());
+        path.push(next);
+    }
+
+    None
+}
+
+/// Describes the sequence `x0, f(x0), f(f(x0)), ...` of an iterated function:
+/// the first `start` values are unique, after which values repeat every `length` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Maps the `n`th iteration onto an equivalent iteration before the cycle repeats,
+    /// e.g. to look up the state after a billion steps from the ones already simulated.
+    #[must_use]
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Floyd's tortoise and hare cycle detection for iterated functions, using constant memory.
+pub fn floyd<S: Clone + Eq>(initial: S, f: impl Fn(&S) -> S) -> Cycle {
+    let mut tortoise = f(&initial);
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&f(&hare));
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = f(&tortoise);
+    while tortoise != hare {
+        hare = f(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Brent's cycle detection for iterated functions, usually needing fewer evaluations than [`floyd`].
+pub fn brent<S: Clone + Eq>(initial: S, f: impl Fn(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = f(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = f(&hare);
+        length += 1;
+    }
+
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = f(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{
+        astar, bfs, bfs_reachable, brent, connected_components, dijkstra, find_cycle, floyd,
+        topological_sort, Cycle,
+    };
+
+    const GRID: [&str; 5] = ["S..#.", ".#.#.", ".#...", ".####", "....G"];
+
+    fn grid_neighbours(&(x, y): &(usize, usize)) -> Vec<(usize, usize)> {
+        [(0, -1), (0, 1), (-1, 0), (1, 0)]
+            .into_iter()
+            .filter_map(|(dx, dy)| Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)))
+            .filter(|&(x, y)| GRID.get(y).and_then(|row| row.as_bytes().get(x)) != Some(&b'#'))
+            .filter(|&(x, y)| y < GRID.len() && x < GRID[0].len())
+            .collect()
+    }
+
+    #[test]
+    fn bfs_finds_shortest_path() {
+        let path = bfs((0, 0), grid_neighbours, |&p| p == (4, 4)).unwrap();
+        assert_eq!(path.cost, 8);
+        assert_eq!(path.states.len(), 9);
+        assert_eq!(path.states.first(), Some(&(0, 0)));
+        assert_eq!(path.states.last(), Some(&(4, 4)));
+        assert!(bfs((0, 0), grid_neighbours, |&p| p == (1, 1)).is_none());
+
+        // shortest way from 1 to 10 using `+1` and `*2`.
+        let path = bfs(1, |&n: &u32| [n + 1, n * 2], |&n| n == 10).unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.states, [1, 2, 4, 5, 10]);
+    }
+
+    #[test]
+    fn bfs_reachable_counts_distances() {
+        let distances = bfs_reachable((0, 0), grid_neighbours);
+        assert_eq!(distances[&(0, 0)], 0);
+        assert_eq!(distances[&(4, 0)], 8);
+        assert_eq!(distances.len(), 17);
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheaper_paths() {
+        // a -> b is expensive, a -> c -> b is cheaper.
+        let edges = |n: &char| match n {
+            'a' => vec![('b', 10), ('c', 1)],
+            'c' => vec![('b', 2)],
+            'b' => vec![('d', 1)],
+            _ => vec![],
+        };
+        let path = dijkstra('a', edges, |&n| n == 'd').unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.states, ['a', 'c', 'b', 'd']);
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let weighted = |p: &(usize, usize)| grid_neighbours(p).into_iter().map(|n| (n, 1));
+        let heuristic = |&(x, y): &(usize, usize)| 4 - x + 4 - y;
+        let path = astar((0, 0), weighted, heuristic, |&p| p == (4, 4)).unwrap();
+        assert_eq!(path.cost, 8);
+        assert_eq!(
+            dijkstra((0, 0), weighted, |&p| p == (4, 4)).unwrap().cost,
+            8
+        );
+    }
+
+    #[test]
+    fn astar_reopens_states_with_inconsistent_heuristic() {
+        // 'c' is first reached through the dearer 'b', as the heuristic overrates 'a'.
+        let edges = |s: &char| match s {
+            's' => vec![('a', 1), ('b', 1)],
+            'a' => vec![('c', 1)],
+            'b' => vec![('c', 2)],
+            'c' => vec![('g', 10)],
+            _ => vec![],
+        };
+        let heuristic = |s: &char| if *s == 'a' { 10 } else { 0 };
+        let path = astar('s', edges, heuristic, |&s| s == 'g').unwrap();
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.states, ['s', 'a', 'c', 'g']);
+    }
+
+    #[test]
+    fn sorts_topologically() {
+        let rules = [(47, 53), (97, 13), (97, 47), (75, 53), (53, 13), (75, 47)];
+        let successors = |n: &u32| {
+            rules
+                .iter()
+                .filter(|(before, _)| before == n)
+                .map(|(_, after)| *after)
+                .collect::<Vec<_>>()
+        };
+        let sorted = topological_sort([13, 47, 53, 97, 75], successors).unwrap();
+        for (before, after) in rules {
+            let position = |n| sorted.iter().position(|x| *x == n).unwrap();
+            assert!(position(before) < position(after));
+        }
+
+        let cyclic = |n: &u32| vec![(n + 1) % 3];
+        assert!(topological_sort([0, 1, 2], cyclic).is_err());
+    }
+
+    #[test]
+    fn finds_connected_components() {
+        let neighbours = |n: &u32| match n {
+            1 => vec![2],
+            2 => vec![1],
+            3 => vec![4],
+            4 => vec![3],
+            _ => vec![],
+        };
+        let components = connected_components([1, 2, 3, 4, 5], neighbours);
+        assert_eq!(
+            components,
+            vec![
+                HashSet::from([1, 2]),
+                HashSet::from([3, 4]),
+                HashSet::from([5])
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_cycles_in_graphs() {
+        let successors = |n: &u32| match n {
+            1 => vec![2],
+            2 => vec![3, 4],
+            3 => vec![],
+            4 => vec![2],
+            _ => vec![],
+        };
+        assert_eq!(find_cycle(1, successors), Some(vec![2, 4, 2]));
+        assert_eq!(find_cycle(3, successors), None);
+    }
+
+    #[test]
+    fn detects_cycles_of_iterated_functions() {
+        // 0 -> 1 -> 2 -> 3 -> 4 -> 2 ...
+        let f = |n: &u32| if *n == 4 { 2 } else { n + 1 };
+        let expected = Cycle {
+            start: 2,
+            length: 3,
+        };
+        assert_eq!(floyd(0, f), expected);
+        assert_eq!(brent(0, f), expected);
+        assert_eq!(expected.equivalent_step(1), 1);
+        assert_eq!(expected.equivalent_step(1_000_000_000), 4);
+
+        let fixed = |_: &u32| 7;
+        assert_eq!(
+            brent(7, fixed),
+            Cycle {
+                start: 0,
+                length: 1
+            }
+        );
+    }
+}
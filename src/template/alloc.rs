@@ -0,0 +1,179 @@
+//! A global allocator that counts allocations, installed by `solution!` with the `count-allocations` feature.
+//! Unlike the dhat profiler it only keeps a few counters, so it can stay installed while a part is benched
+//! and its figures are printed by the runner next to the duration.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+static LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Wraps the system allocator, counting every allocation made through it.
+pub struct CountingAlloc;
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+    }
+
+    /// A reallocation counts as a new allocation of `new_size` bytes, as it does in dhat.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            LIVE_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+            record_allocation(new_size);
+        }
+        new_ptr
+    }
+}
+
+fn record_allocation(size: usize) {
+    let size = size as u64;
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(size, Ordering::Relaxed);
+    let live = LIVE_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+}
+
+/// How `solve` and `time` count the allocations of a solution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AllocationCounting {
+    #[default]
+    Off,
+    /// Build with the counting allocator, printing allocations next to each duration (`--allocs`).
+    Counter,
+    /// Run with the dhat heap profiler (`--dhat`).
+    Dhat,
+}
+
+/// Allocations made while running a part.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocations: u64,
+    /// Total bytes allocated, including memory that was freed again.
+    pub bytes: u64,
+    /// Most bytes live at once, on top of what was allocated before the part ran.
+    pub peak_bytes: u64,
+}
+
+/// Runs `func`, counting the allocations it makes.
+/// All counts are zero if the counting allocator is not installed.
+pub fn measure<T>(func: impl FnOnce() -> T) -> (T, AllocStats) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = BYTES.load(Ordering::Relaxed);
+    let live = LIVE_BYTES.load(Ordering::Relaxed);
+    PEAK_BYTES.store(live, Ordering::Relaxed);
+
+    let result = func();
+
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes: BYTES.load(Ordering::Relaxed) - bytes,
+        peak_bytes: PEAK_BYTES.load(Ordering::Relaxed).saturating_sub(live),
+    };
+
+    (result, stats)
+}
+
+/// Formats as printed by the runner, e.g. `12 allocations, 1024 bytes, peak 800 bytes`.
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} bytes, peak {} bytes",
+            self.allocations, self.bytes, self.peak_bytes
+        )
+    }
+}
+
+impl FromStr for AllocStats {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("expected allocation stats, found '{s}'.");
+        let number = |value: Option<&str>, suffix: &str| -> Result<u64, String> {
+            value
+                .and_then(|value| value.trim().strip_suffix(suffix))
+                .and_then(|value| value.trim().parse().ok())
+                .ok_or_else(error)
+        };
+
+        let mut parts = s.split(',');
+        let allocations = number(parts.next(), "allocations")?;
+        let bytes = number(parts.next(), "bytes")?;
+        let peak_bytes = number(
+            parts
+                .next()
+                .and_then(|peak| peak.trim().strip_prefix("peak")),
+            "bytes",
+        )?;
+
+        if parts.next().is_some() {
+            return Err(error());
+        }
+
+        Ok(AllocStats {
+            allocations,
+            bytes,
+            peak_bytes,
+        })
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{measure, AllocStats};
+
+    #[test]
+    fn round_trips_printed_stats() {
+        let stats = AllocStats {
+            allocations: 12,
+            bytes: 1024,
+            peak_bytes: 800,
+        };
+        assert_eq!(
+            stats.to_string(),
+            "12 allocations, 1024 bytes, peak 800 bytes"
+        );
+        assert_eq!(stats.to_string().parse(), Ok(stats));
+    }
+
+    #[test]
+    fn rejects_malformed_stats() {
+        assert!("12 allocations".parse::<AllocStats>().is_err());
+        assert!("1 allocations, 2 bytes, 3 bytes"
+            .parse::<AllocStats>()
+            .is_err());
+        assert!("a allocations, 2 bytes, peak 3 bytes"
+            .parse::<AllocStats>()
+            .is_err());
+    }
+
+    #[test]
+    fn counts_nothing_without_the_allocator() {
+        let (result, stats) = measure(|| vec![1, 2, 3].len());
+        assert_eq!(result, 3);
+        assert_eq!(stats, AllocStats::default());
+    }
+}
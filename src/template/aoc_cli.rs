@@ -1,4 +1,5 @@
-/// Wrapper module around the "aoc-cli" command-line.
+//! Wrapper module around the "aoc-cli" command-line.
+
 use std::{
     fmt::Display,
     process::{Command, Output, Stdio},
@@ -77,7 +78,18 @@ pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandErro
     let mut args = build_args("submit", &[], day);
     args.push(part.to_string());
     args.push(result.to_string());
-    call_aoc_cli(&args)
+
+    // capture the response to tell whether the answer was accepted, then echo it.
+    let output = run_aoc_cli(&args, Stdio::piped());
+    if let Ok(output) | Err(AocCommandError::BadExitStatus(output)) = &output {
+        print!("{}", String::from_utf8_lossy(&output.stdout));
+    }
+    output
+}
+
+/// Whether the response to [`submit`] says the answer is correct.
+pub fn is_accepted(output: &Output) -> bool {
+    String::from_utf8_lossy(&output.stdout).contains("That's the right answer")
 }
 
 fn get_input_path(day: Day) -> String {
@@ -88,7 +100,7 @@ fn get_puzzle_path(day: Day) -> String {
     format!("data/puzzles/{day}.md")
 }
 
-fn get_year() -> Option<u16> {
+pub fn get_year() -> Option<u16> {
     match std::env::var("AOC_YEAR") {
         Ok(x) => x.parse().ok().or(None),
         Err(_) => None,
@@ -109,10 +121,14 @@ fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
 }
 
 fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
+    run_aoc_cli(args, Stdio::inherit())
+}
+
+fn run_aoc_cli(args: &[String], stdout: Stdio) -> Result<Output, AocCommandError> {
     // println!("Calling >aoc with: {}", args.join(" "));
     let output = Command::new("aoc")
         .args(args)
-        .stdout(Stdio::inherit())
+        .stdout(stdout)
         .stderr(Stdio::inherit())
         .output()
         .map_err(|_| AocCommandError::CommandNotCallable)?;
@@ -1,5 +1,20 @@
-use crate::template::{all_days, run_multi::run_multi};
+use std::process;
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+use crate::template::{all_days, run_multi::run_multi, ReportFormat, RunOptions};
+
+/// With a `format` other than text, only a report of the run is printed to stdout.
+/// Exits with a non-zero status if a day failed or timed out.
+pub fn handle(is_release: bool, options: RunOptions, format: ReportFormat) {
+    let quiet = format != ReportFormat::Text;
+    let (report, _) = run_multi(&all_days().collect(), is_release, false, options, quiet);
+
+    if let Some(report) = report.render(format) {
+        println!("{}", report.trim_end());
+    }
+
+    let failures = report.failures();
+    if failures > 0 {
+        eprintln!("{failures} day(s) failed or timed out.");
+        process::exit(1);
+    }
 }
@@ -0,0 +1,29 @@
+use std::process::{self, Command, Stdio};
+
+use crate::template::input::InputSource;
+use crate::template::Day;
+
+/// Lints the input of a day and runs the day's validator on it, without solving it.
+pub fn handle(day: Day, input: &InputSource) {
+    let mut cmd_args = vec![
+        "run".to_string(),
+        "--quiet".to_string(),
+        "--bin".to_string(),
+        day.to_string(),
+        "--".to_string(),
+        "--check".to_string(),
+    ];
+    cmd_args.extend(input.to_args());
+
+    let mut cmd = Command::new("cargo")
+        .args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+
+    let status = cmd.wait().unwrap();
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
+}
@@ -0,0 +1,511 @@
+//! An interactive overview of the calendar, drawn with plain ANSI escape codes.
+//! The terminal is switched to raw mode with `stty`, so single key presses are read without waiting for enter.
+//! Where `stty` is not available, keys are read line by line instead.
+
+use std::collections::HashMap;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::time::Duration;
+
+use crate::template::readme_stars::{DayStars, Stars};
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::timings::{Timing, Timings};
+use crate::template::{all_days, aoc_cli, data_path, Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+const ANSI_DIM: &str = "\x1b[2m";
+const ANSI_REVERSE: &str = "\x1b[7m";
+
+pub fn handle(timings_path: &Path) {
+    let mut dashboard = Dashboard {
+        timings_path: timings_path.to_path_buf(),
+        days: vec![],
+        total_stars: 0,
+        tests: HashMap::new(),
+        selected: 0,
+        message: None,
+    };
+
+    let result = dashboard.load().and_then(|()| {
+        if io::stdin().is_terminal() && io::stdout().is_terminal() {
+            dashboard.run()
+        } else {
+            // nothing to interact with, e.g. when piped into a file.
+            println!("{}", dashboard.render(false));
+            Ok(())
+        }
+    });
+
+    if let Err(e) = result {
+        eprintln!("Error: {e}");
+        process::exit(1);
+    }
+}
+
+/// What is known about a day, as shown in its cell of the grid.
+struct DayStatus {
+    day: Day,
+    scaffolded: bool,
+    input: bool,
+    /// Whether the example tests passed, `None` if they were not run during this session.
+    tests: Option<bool>,
+    stars: DayStars,
+    timing: Option<Timing>,
+}
+
+impl DayStatus {
+    /// A cell of the grid, e.g. `05 ★☆ SI✔   1.2ms`.
+    fn cell(&self) -> String {
+        let star = |earned: bool| if earned { '★' } else { '☆' };
+        let flag = |set: bool, c: char| if set { c } else { '·' };
+        let tests = match self.tests {
+            Some(true) => '✔',
+            Some(false) => '✖',
+            None => '·',
+        };
+
+        format!(
+            "{} {}{} {}{}{} {:>8}",
+            self.day,
+            star(self.stars.part_1),
+            star(self.stars.part_2),
+            flag(self.scaffolded, 'S'),
+            flag(self.input, 'I'),
+            tests,
+            self.timing.as_ref().map_or(String::new(), format_timing),
+        )
+    }
+
+    fn details(&self) -> Vec<String> {
+        let found = |found: bool| if found { "" } else { " (missing)" };
+        let tests = match self.tests {
+            Some(true) => "passing",
+            Some(false) => "failing",
+            None => "not run yet, press `t`",
+        };
+        let timing = self.timing.as_ref().map_or_else(
+            || "not benched yet, press `b`".to_string(),
+            |timing| {
+                let parts = [
+                    ("Parse", &timing.parse),
+                    ("Part 1", &timing.part_1),
+                    ("Part 2", &timing.part_2),
+                ];
+                let parts: Vec<String> = parts
+                    .iter()
+                    .filter_map(|(label, part)| {
+                        part.as_ref()
+                            .map(|part| format!("{label} {}", part.duration))
+                    })
+                    .collect();
+                format!("{} ({})", format_timing(timing), parts.join(", "))
+            },
+        );
+
+        vec![
+            format!("{ANSI_BOLD}Day {}{ANSI_RESET}", self.day),
+            format!(
+                "  Solution  {}{}",
+                get_path_for_bin(self.day),
+                found(self.scaffolded)
+            ),
+            format!(
+                "  Input     data/inputs/{}.txt{}",
+                self.day,
+                found(self.input)
+            ),
+            format!("  Examples  {tests}"),
+            format!(
+                "  Stars     {}",
+                match (self.stars.part_1, self.stars.part_2) {
+                    (true, true) => "★★",
+                    (true, false) => "★☆",
+                    (false, true) => "☆★",
+                    (false, false) => "☆☆",
+                }
+            ),
+            format!("  Timing    {timing}"),
+        ]
+    }
+}
+
+fn format_timing(timing: &Timing) -> String {
+    if timing.timed_out_nanos.is_some() {
+        return "⏱".into();
+    }
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let duration = Duration::from_nanos(timing.total_nanos as u64);
+    format!("{duration:.1?}")
+}
+
+/// What can be done with the selected day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    Run,
+    Test,
+    Bench,
+    Read,
+}
+
+impl Action {
+    /// The cargo alias that performs the action, e.g. `cargo solve 05`.
+    fn command(self, day: Day) -> Command {
+        let day = day.to_string();
+        let mut command = Command::new("cargo");
+        match self {
+            Action::Run => command.args(["solve", &day]),
+            Action::Test => command.args(["test", "--bin", &day]),
+            Action::Bench => command.args(["time", &day, "--store"]),
+            Action::Read => command.args(["read", &day]),
+        };
+        command
+    }
+}
+
+struct Dashboard {
+    timings_path: PathBuf,
+    days: Vec<DayStatus>,
+    total_stars: usize,
+    /// Outcomes of the example tests run during this session.
+    tests: HashMap<Day, bool>,
+    /// Index of the selected day in `days`.
+    selected: usize,
+    /// Shown below the grid until the next key press.
+    message: Option<String>,
+}
+
+impl Dashboard {
+    /// Reads the state of every day from disk, e.g. after an action changed it.
+    fn load(&mut self) -> Result<(), String> {
+        let timings = Timings::read_from_file(&self.timings_path)?;
+        let stars = Stars::collect(&timings)?;
+
+        self.total_stars = stars.total();
+        self.days = all_days()
+            .map(|day| DayStatus {
+                day,
+                scaffolded: Path::new(&get_path_for_bin(day)).exists(),
+                input: data_path("inputs", &format!("{day}.txt")).exists(),
+                tests: self.tests.get(&day).copied(),
+                stars: stars
+                    .days
+                    .iter()
+                    .find(|(d, _)| *d == day)
+                    .map(|(_, stars)| *stars)
+                    .unwrap_or_default(),
+                timing: timings.data.iter().find(|t| t.day == day).cloned(),
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    fn run(&mut self) -> Result<(), String> {
+        let mut terminal = Terminal::enter();
+
+        loop {
+            terminal.draw(&self.render(true));
+            let key = terminal.read_key()?;
+            self.message = None;
+
+            match key {
+                Key::Char('q') | Key::Escape | Key::Interrupt => return Ok(()),
+                Key::Left | Key::Char('h') => self.move_selection(-1),
+                Key::Right | Key::Char('l') => self.move_selection(1),
+                Key::Up | Key::Char('k') => self.move_selection(-5),
+                Key::Down | Key::Char('j') => self.move_selection(5),
+                Key::Enter | Key::Char('r') => self.perform(&mut terminal, Action::Run)?,
+                Key::Char('t') => self.perform(&mut terminal, Action::Test)?,
+                Key::Char('b') => self.perform(&mut terminal, Action::Bench)?,
+                Key::Char('p') => self.perform(&mut terminal, Action::Read)?,
+                Key::Char('a') => self.test_all(&mut terminal)?,
+                Key::Char(_) => {}
+            }
+        }
+    }
+
+    /// Moves the selection through the grid, stopping at its edges.
+    fn move_selection(&mut self, by: isize) {
+        let last = self.days.len().saturating_sub(1);
+        if let Some(selected) = self.selected.checked_add_signed(by) {
+            self.selected = selected.min(last);
+        }
+    }
+
+    /// Runs an action for the selected day below the dashboard, then returns to it.
+    fn perform(&mut self, terminal: &mut Terminal, action: Action) -> Result<(), String> {
+        let day = self.days[self.selected].day;
+
+        if !self.days[self.selected].scaffolded && action != Action::Read {
+            self.message = Some(format!(
+                "Day {day} is not scaffolded yet. Run `cargo scaffold {day}` first."
+            ));
+            return Ok(());
+        }
+        if action == Action::Read && aoc_cli::check().is_err() {
+            self.message = Some("Reading puzzles needs aoc-cli, see the readme.".into());
+            return Ok(());
+        }
+
+        terminal.suspend();
+        let status = action
+            .command(day)
+            .status()
+            .map_err(|e| format!("could not run cargo: {e}"))?;
+        if action == Action::Test {
+            self.tests.insert(day, status.success());
+        }
+        terminal.wait_for_enter()?;
+        terminal.resume();
+
+        self.load()
+    }
+
+    /// Runs the example tests of every scaffolded day.
+    fn test_all(&mut self, terminal: &mut Terminal) -> Result<(), String> {
+        terminal.suspend();
+
+        for day in self
+            .days
+            .iter()
+            .filter(|day| day.scaffolded)
+            .map(|day| day.day)
+        {
+            print!("Day {day}: ");
+            let _ = io::stdout().flush();
+            let passed = Action::Test
+                .command(day)
+                .arg("--quiet")
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map_err(|e| format!("could not run cargo: {e}"))?
+                .success();
+            println!("{}", if passed { "✔" } else { "✖" });
+            self.tests.insert(day, passed);
+        }
+
+        terminal.wait_for_enter()?;
+        terminal.resume();
+
+        self.load()
+    }
+
+    fn render(&self, interactive: bool) -> String {
+        let title = aoc_cli::get_year().map_or_else(
+            || "Advent of Code".into(),
+            |year| format!("Advent of Code {year}"),
+        );
+        let mut lines = vec![
+            format!(
+                "{ANSI_BOLD}{title}: {} / 50 ★{ANSI_RESET}",
+                self.total_stars
+            ),
+            String::new(),
+        ];
+
+        for (row, days) in self.days.chunks(5).enumerate() {
+            let cells: Vec<String> = days
+                .iter()
+                .enumerate()
+                .map(|(column, day)| {
+                    if interactive && row * 5 + column == self.selected {
+                        format!("{ANSI_REVERSE}{}{ANSI_RESET}", day.cell())
+                    } else if !day.scaffolded {
+                        format!("{ANSI_DIM}{}{ANSI_RESET}", day.cell())
+                    } else {
+                        day.cell()
+                    }
+                })
+                .collect();
+            lines.push(cells.join("  "));
+        }
+
+        lines.push(String::new());
+        lines.push(format!(
+            "{ANSI_ITALIC}S scaffolded · I input downloaded · ✔ ✖ example tests · ⏱ timed out{ANSI_RESET}"
+        ));
+
+        if interactive {
+            lines.push(String::new());
+            if let Some(day) = self.days.get(self.selected) {
+                lines.extend(day.details());
+            }
+            lines.push(String::new());
+            lines.push(format!(
+                "{ANSI_ITALIC}arrows/hjkl move · enter/r run · t test · a test all · b bench · p puzzle · q quit{ANSI_RESET}"
+            ));
+            if let Some(message) = &self.message {
+                lines.push(message.clone());
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Escape,
+    /// Ctrl-C, which is read as a key as raw mode does not turn it into a signal.
+    Interrupt,
+    Char(char),
+}
+
+/// Parses the bytes of a single key press, e.g. `\x1b[A` for the up arrow.
+fn parse_key(bytes: &[u8]) -> Option<Key> {
+    match bytes {
+        [0x1b, b'[', b'A', ..] => Some(Key::Up),
+        [0x1b, b'[', b'B', ..] => Some(Key::Down),
+        [0x1b, b'[', b'C', ..] => Some(Key::Right),
+        [0x1b, b'[', b'D', ..] => Some(Key::Left),
+        [0x1b] => Some(Key::Escape),
+        [0x03, ..] => Some(Key::Interrupt),
+        [b'\r' | b'\n', ..] => Some(Key::Enter),
+        _ => std::str::from_utf8(bytes)
+            .ok()?
+            .chars()
+            .next()
+            .map(Key::Char),
+    }
+}
+
+/// The terminal while the dashboard is shown, on the alternate screen and in raw mode.
+/// The previous state is restored when it is dropped, so a panic does not leave the terminal unusable.
+struct Terminal {
+    /// Settings saved by `stty -g`, `None` if the terminal could not be switched to raw mode.
+    saved: Option<String>,
+}
+
+impl Terminal {
+    fn enter() -> Self {
+        let mut terminal = Terminal { saved: None };
+        terminal.resume();
+        terminal
+    }
+
+    /// Leaves the dashboard, e.g. to show the output of a command.
+    fn suspend(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        if let Some(saved) = self.saved.take() {
+            let _ = stty(&[&saved]);
+        }
+    }
+
+    fn resume(&mut self) {
+        self.saved = stty(&["-g"]).ok();
+        if self.saved.is_some() && stty(&["-icanon", "-echo", "-isig", "min", "1"]).is_err() {
+            self.saved = None;
+        }
+        print!("\x1b[?1049h\x1b[?25l");
+        let _ = io::stdout().flush();
+    }
+
+    fn draw(&self, screen: &str) {
+        print!("\x1b[H\x1b[2J{screen}");
+        let _ = io::stdout().flush();
+    }
+
+    fn read_key(&self) -> Result<Key, String> {
+        let error = |e: io::Error| format!("could not read from the terminal: {e}");
+
+        if self.saved.is_none() {
+            // without raw mode, a key only arrives with the line it was typed on.
+            let mut line = String::new();
+            io::stdin().read_line(&mut line).map_err(error)?;
+            return Ok(parse_key(line.as_bytes()).unwrap_or(Key::Escape));
+        }
+
+        loop {
+            let mut buffer = [0; 8];
+            let read = io::stdin().read(&mut buffer).map_err(error)?;
+            if read == 0 {
+                return Ok(Key::Escape);
+            }
+            if let Some(key) = parse_key(&buffer[..read]) {
+                return Ok(key);
+            }
+        }
+    }
+
+    fn wait_for_enter(&self) -> Result<(), String> {
+        print!("\n{ANSI_ITALIC}Press enter to return to the dashboard.{ANSI_RESET}");
+        let _ = io::stdout().flush();
+        io::stdin()
+            .read_line(&mut String::new())
+            .map(|_| ())
+            .map_err(|e| format!("could not read from the terminal: {e}"))
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        self.suspend();
+    }
+}
+
+/// Runs `stty` on the terminal of this process, returning what it printed.
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_key, DayStatus, Key};
+    use crate::day;
+    use crate::template::readme_stars::DayStars;
+    use crate::template::timings::{PartTiming, Timing};
+
+    #[test]
+    fn parses_keys() {
+        assert_eq!(parse_key(b"\x1b[A"), Some(Key::Up));
+        assert_eq!(parse_key(b"\x1b[D"), Some(Key::Left));
+        assert_eq!(parse_key(b"\x1b"), Some(Key::Escape));
+        assert_eq!(parse_key(b"\x03"), Some(Key::Interrupt));
+        assert_eq!(parse_key(b"\n"), Some(Key::Enter));
+        assert_eq!(parse_key(b"t\n"), Some(Key::Char('t')));
+        assert_eq!(parse_key(&[0xff]), None);
+    }
+
+    #[test]
+    fn renders_cells() {
+        let mut status = DayStatus {
+            day: day!(5),
+            scaffolded: true,
+            input: false,
+            tests: Some(true),
+            stars: DayStars {
+                part_1: true,
+                part_2: false,
+            },
+            timing: Some(Timing {
+                day: day!(5),
+                parse: None,
+                part_1: PartTiming::from_duration("1.2ms", Some(100)),
+                part_2: None,
+                total_nanos: 1_200_000.0,
+                timed_out_nanos: None,
+            }),
+        };
+        assert_eq!(status.cell(), "05 ★☆ S·✔    1.2ms");
+
+        status.tests = None;
+        status.timing = None;
+        assert_eq!(status.cell(), "05 ★☆ S··         ");
+    }
+}
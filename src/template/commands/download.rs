@@ -1,14 +1,11 @@
 use crate::template::{aoc_cli, Day};
-use std::process;
 
-pub fn handle(day: Day) {
+pub fn handle(day: Day) -> Result<(), String> {
     if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+        return Err("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.".into());
     }
 
-    if let Err(e) = aoc_cli::download(day) {
-        eprintln!("failed to call aoc-cli: {e}");
-        process::exit(1);
-    };
+    aoc_cli::download(day)
+        .map(|_| ())
+        .map_err(|e| format!("failed to call aoc-cli: {e}"))
 }
@@ -1,6 +1,9 @@
 pub mod all;
+pub mod check;
+pub mod dashboard;
 pub mod download;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
+pub mod stars;
 pub mod time;
@@ -1,79 +1,283 @@
-use std::{
-    fs::{File, OpenOptions},
-    io::Write,
-    process,
-};
+use std::{fmt::Display, fs, io::ErrorKind};
 
-use crate::template::Day;
+use crate::template::examples::{self, Answer, PuzzleExamples};
+use crate::template::{aoc_cli, Day};
 
 const MODULE_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
 
-fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
-    let mut file = OpenOptions::new();
-    if overwrite {
-        file.create(true);
-    } else {
-        file.create_new(true);
+/// Directory of the named templates selectable with `--template <name>`.
+const TEMPLATES_DIR: &str = "templates";
+
+/// The helper call that reads the example an answer belongs to.
+fn example_reader(answer: Option<&Answer>) -> String {
+    match answer {
+        Some(answer) if answer.example > 1 => {
+            format!("read_file_part(\"examples\", DAY, {})", answer.example)
+        }
+        _ => "read_file(\"examples\", DAY)".to_string(),
     }
-    file.truncate(true).write(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)
+/// The expected test result; only numeric answers fit the template's return type.
+fn expected_result(answer: Option<&Answer>) -> String {
+    match answer {
+        Some(answer) if answer.value.parse::<u32>().is_ok() => format!("Some({})", answer.value),
+        _ => "None".to_string(),
+    }
+}
+
+/// Reads `templates/<name>.txt`, or the built-in template if no name is given.
+fn load_template(name: Option<&str>) -> Result<String, String> {
+    let Some(name) = name else {
+        return Ok(MODULE_TEMPLATE.to_string());
+    };
+
+    fs::read_to_string(format!("{TEMPLATES_DIR}/{name}.txt")).map_err(|e| {
+        let mut available: Vec<String> = fs::read_dir(TEMPLATES_DIR)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                (path.extension()? == "txt")
+                    .then(|| path.file_stem()?.to_str().map(String::from))?
+            })
+            .collect();
+        available.sort();
+        format!(
+            "could not read template \"{name}\": {e}. Available templates: {}",
+            available.join(", ")
+        )
+    })
 }
 
-pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+/// Substitutes the placeholders of a module template.
+fn render(template: &str, day: Day, puzzle: &PuzzleExamples) -> String {
+    let title = puzzle
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Day {}", day.into_inner()));
+    let year = aoc_cli::get_year().map_or_else(String::new, |year| year.to_string());
+
+    template
+        .replace("%DAY_NUMBER%", &day.into_inner().to_string())
+        .replace("%YEAR%", &year)
+        .replace("%TITLE%", &title)
+        .replace(
+            "%PART_ONE_EXAMPLE%",
+            &example_reader(puzzle.part_one.as_ref()),
+        )
+        .replace(
+            "%PART_ONE_ANSWER%",
+            &expected_result(puzzle.part_one.as_ref()),
+        )
+        .replace(
+            "%PART_TWO_EXAMPLE%",
+            &example_reader(puzzle.part_two.as_ref()),
+        )
+        .replace(
+            "%PART_TWO_ANSWER%",
+            &expected_result(puzzle.part_two.as_ref()),
+        )
+}
+
+/// Which kinds of existing files may be replaced.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Overwrite {
+    pub module: bool,
+    pub examples: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Module,
+    Input,
+    Example,
+}
 
-    let mut file = match safe_create_file(&module_path, overwrite) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Module => write!(f, "module"),
+            Kind::Input => write!(f, "input"),
+            Kind::Example => write!(f, "example"),
         }
-    };
+    }
+}
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Create,
+    Overwrite,
+    Skip,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Create => write!(f, "create"),
+            Action::Overwrite => write!(f, "overwrite"),
+            Action::Skip => write!(f, "skip"),
         }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
+    }
+}
+
+/// Decides what to do with a file. Empty files hold nothing worth keeping and are filled in.
+fn decide(existing: Option<&str>, overwrite: bool) -> Action {
+    match existing {
+        None => Action::Create,
+        Some(contents) if contents.trim().is_empty() => Action::Create,
+        Some(_) if overwrite => Action::Overwrite,
+        Some(_) => Action::Skip,
+    }
+}
+
+struct PlannedFile {
+    kind: Kind,
+    path: String,
+    contents: String,
+    action: Action,
+}
+
+impl PlannedFile {
+    fn new(kind: Kind, path: String, contents: String, overwrite: bool) -> Result<Self, String> {
+        let existing = match fs::read_to_string(&path) {
+            Ok(existing) => Some(existing),
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(format!("could not read {kind} file \"{path}\": {e}")),
+        };
+
+        Ok(PlannedFile {
+            action: decide(existing.as_deref(), overwrite),
+            kind,
+            path,
+            contents,
+        })
+    }
+
+    fn skip_hint(&self) -> &'static str {
+        match self.kind {
+            Kind::Module => " (exists, pass --overwrite to replace it)",
+            Kind::Example => " (exists, pass --overwrite-examples to replace it)",
+            Kind::Input => " (exists, use `cargo download` to replace it)",
         }
     }
+}
+
+/// The files `scaffold` creates for a day and what happens to each of them.
+pub struct Plan {
+    files: Vec<PlannedFile>,
+}
+
+impl Plan {
+    pub fn new(day: Day, template: Option<&str>, overwrite: Overwrite) -> Result<Self, String> {
+        let template = load_template(template)?;
+
+        // pre-fill examples and answers if the puzzle description was downloaded already.
+        let puzzle = fs::read_to_string(format!("data/puzzles/{day}.md"))
+            .map(|markdown| examples::extract(&markdown))
+            .unwrap_or_default();
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+        let mut files = vec![
+            PlannedFile::new(
+                Kind::Module,
+                format!("src/bin/{day}.rs"),
+                render(&template, day, &puzzle),
+                overwrite.module,
+            )?,
+            // inputs are only ever written by `download`.
+            PlannedFile::new(
+                Kind::Input,
+                format!("data/inputs/{day}.txt"),
+                String::new(),
+                false,
+            )?,
+        ];
+
+        if puzzle.examples.is_empty() {
+            files.push(PlannedFile::new(
+                Kind::Example,
+                format!("data/examples/{day}.txt"),
+                String::new(),
+                overwrite.examples,
+            )?);
         }
-        Err(e) => {
-            eprintln!("Failed to create input file: {e}");
-            process::exit(1);
+
+        for (index, example) in puzzle.examples.iter().enumerate() {
+            files.push(PlannedFile::new(
+                Kind::Example,
+                format!(
+                    "data/examples/{}",
+                    PuzzleExamples::file_name(day, index + 1)
+                ),
+                example.clone(),
+                overwrite.examples,
+            )?);
         }
+
+        Ok(Plan { files })
     }
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
+    pub fn print(&self) {
+        for file in &self.files {
+            let hint = if file.action == Action::Skip {
+                file.skip_hint()
+            } else {
+                ""
+            };
+            println!(
+                "{:<9}  {:<7}  \"{}\"{hint}",
+                file.action.to_string(),
+                file.kind.to_string(),
+                file.path
+            );
         }
-        Err(e) => {
-            eprintln!("Failed to create example file: {e}");
-            process::exit(1);
+    }
+
+    pub fn execute(&self) -> Result<(), String> {
+        for file in self.files.iter().filter(|file| file.action != Action::Skip) {
+            fs::write(&file.path, &file.contents).map_err(|e| {
+                format!("failed to write {} file \"{}\": {e}", file.kind, file.path)
+            })?;
         }
+        Ok(())
+    }
+}
+
+pub fn handle(
+    day: Day,
+    overwrite: Overwrite,
+    template: Option<&str>,
+    dry_run: bool,
+) -> Result<(), String> {
+    let plan = Plan::new(day, template, overwrite)?;
+    plan.print();
+
+    if dry_run {
+        println!("---");
+        println!("Dry run, no files were written.");
+        return Ok(());
     }
 
+    plan.execute()?;
+
     println!("---");
     println!("🎄 Type `cargo solve {day}` to run your solution.");
+    Ok(())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{decide, Action};
+
+    #[test]
+    fn never_replaces_contents_without_overwrite() {
+        assert_eq!(decide(None, false), Action::Create);
+        assert_eq!(decide(Some(""), false), Action::Create);
+        assert_eq!(decide(Some("\n"), true), Action::Create);
+        assert_eq!(decide(Some("3   4"), false), Action::Skip);
+        assert_eq!(decide(Some("3   4"), true), Action::Overwrite);
+    }
 }
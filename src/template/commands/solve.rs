@@ -1,11 +1,27 @@
-use std::process::{Command, Stdio};
+use std::process::{self, Command, Stdio};
 
+use crate::template::alloc::AllocationCounting;
+use crate::template::input::InputSource;
 use crate::template::Day;
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+pub fn handle(
+    day: Day,
+    release: bool,
+    allocations: AllocationCounting,
+    submit_part: Option<u8>,
+    params: &[String],
+    input: &InputSource,
+) {
+    if submit_part.is_some() && (*input != InputSource::Puzzle || !params.is_empty()) {
+        eprintln!(
+            "Error: `--submit` can only be used with the puzzle input and without `--param`."
+        );
+        process::exit(1);
+    }
+
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
-    if dhat {
+    if allocations == AllocationCounting::Dhat {
         cmd_args.extend([
             "--profile".to_string(),
             "dhat".to_string(),
@@ -16,6 +32,10 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push("--release".to_string());
     }
 
+    if allocations == AllocationCounting::Counter {
+        cmd_args.extend(["--features".to_string(), "count-allocations".to_string()]);
+    }
+
     cmd_args.push("--".to_string());
 
     if let Some(submit_part) = submit_part {
@@ -23,6 +43,13 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
+    for param in params {
+        cmd_args.push("--param".to_string());
+        cmd_args.push(param.clone());
+    }
+
+    cmd_args.extend(input.to_args());
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
@@ -30,5 +57,8 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         .spawn()
         .unwrap();
 
-    cmd.wait().unwrap();
+    let status = cmd.wait().unwrap();
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
 }
@@ -0,0 +1,25 @@
+use std::path::Path;
+use std::process;
+
+use crate::template::readme_stars::{self, Stars};
+use crate::template::timings::Timings;
+use crate::template::{aoc_cli, readme_benchmarks};
+
+pub fn handle(timings_path: &Path) {
+    let stars = Timings::read_from_file(timings_path).and_then(|timings| Stars::collect(&timings));
+    let stars = stars.unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        process::exit(1);
+    });
+
+    println!("{}", stars.calendar(aoc_cli::get_year()));
+    println!();
+
+    match readme_stars::update(&stars) {
+        Ok(()) => println!("Stored updated stars."),
+        Err(readme_benchmarks::Error::Parser(e)) => {
+            eprintln!("Failed to store updated stars: {e}");
+        }
+        Err(_) => eprintln!("Failed to store updated stars."),
+    }
+}
@@ -1,11 +1,40 @@
 use std::collections::HashSet;
+use std::path::Path;
+use std::process;
 
+use crate::template::history::{self, History, Run};
 use crate::template::run_multi::run_multi;
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{
+    all_days, readme_benchmarks, Day, ReportFormat, RunOptions, ANSI_BOLD, ANSI_RESET,
+};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
-    let stored_timings = Timings::read_from_file();
+/// `compare` is the threshold a part's median may regress by before it is flagged, e.g. `0.1` for 10%.
+/// `options` selects what is measured besides the run time and how long solutions may run.
+/// With a `format` other than text, only a report of the run is printed to stdout.
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    compare: Option<f64>,
+    options: RunOptions,
+    format: ReportFormat,
+    timings_path: &Path,
+    history_path: &Path,
+) {
+    let exit_with = |e: String| -> ! {
+        eprintln!("Error: {e}");
+        process::exit(1);
+    };
+
+    // fail before running anything, so a corrupt file is never replaced.
+    let stored_timings = Timings::read_from_file(timings_path).unwrap_or_else(|e| exit_with(e));
+    let history = if store || compare.is_some() {
+        History::read_from_file(history_path).unwrap_or_else(|e| exit_with(e))
+    } else {
+        History::default()
+    };
 
     let days_to_run = day.map_or_else(
         || {
@@ -21,20 +50,73 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let quiet = format != ReportFormat::Text;
+    let (report, timings) = run_multi(&days_to_run, true, true, options, quiet);
+    let timings = timings.unwrap();
+
+    let regressions = compare.map_or(0, |threshold| {
+        let comparisons = history.compare(&timings, &history::machine_id());
+        if quiet {
+            return comparisons
+                .iter()
+                .filter(|comparison| comparison.is_regression(threshold))
+                .count();
+        }
+        println!();
+        history::print_comparisons(&comparisons, threshold)
+    });
 
     if store {
         let merged_timings = stored_timings.merge(&timings);
-        merged_timings.store_file().unwrap();
+        if let Err(e) = merged_timings.store_file(timings_path) {
+            exit_with(e);
+        }
+        if let Err(e) = History::append(history_path, &Run::new(timings)) {
+            exit_with(e);
+        }
 
-        println!();
         match readme_benchmarks::update(merged_timings) {
-            Ok(()) => {
-                println!("Stored updated benchmarks.");
+            Ok(()) if !quiet => {
+                println!("\nStored updated benchmarks.");
+            }
+            Ok(()) => {}
+            Err(readme_benchmarks::Error::Parser(e)) => {
+                eprintln!("Failed to store updated benchmarks: {e}");
             }
             Err(_) => {
                 eprintln!("Failed to store updated benchmarks.");
             }
         }
     }
+
+    if let Some(report) = report.render(format) {
+        println!("{}", report.trim_end());
+    }
+
+    if regressions > 0 {
+        eprintln!("{regressions} part(s) regressed.");
+        process::exit(1);
+    }
+}
+
+/// Prints how the median of each part changed over the recorded runs on this machine.
+pub fn trend(day: Option<Day>, history_path: &Path) {
+    let history = History::read_from_file(history_path).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        process::exit(1);
+    });
+
+    let machine = history::machine_id();
+    let days: Vec<Day> = day.map_or_else(|| all_days().collect(), |day| vec![day]);
+    let trends = history.trends(&machine, &days);
+
+    if trends.is_empty() {
+        println!("No recorded runs on \"{machine}\". Record runs with `cargo time --store`.");
+        return;
+    }
+
+    println!("{ANSI_BOLD}Trends on \"{machine}\":{ANSI_RESET}");
+    for trend in trends {
+        println!("{}", trend.summary());
+    }
 }
@@ -0,0 +1,281 @@
+//! Runs a solution against every input in a directory, to check that it generalises.
+
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant};
+
+use crate::parse::{ParseResult, Span};
+use crate::template::{Day, ANSI_BOLD, ANSI_RESET};
+
+/// A solution part with its answer formatted for display.
+pub type Solver<'a> = Box<dyn Fn(&str) -> Option<String> + 'a>;
+
+pub fn solver<'a, T: Display>(func: impl Fn(&str) -> Option<T> + 'a) -> Solver<'a> {
+    Box::new(move |input| func(input).map(|answer| answer.to_string()))
+}
+
+/// Expected answers for one input, read from `<input>.answers` next to it:
+///
+/// ```text
+/// part_one: 11
+/// part_two: 31
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part_one: Option<String>,
+    pub part_two: Option<String>,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> ParseResult<Self> {
+        let mut answers = Answers::default();
+
+        for line in Span::new(text).lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (key, value) = line.split_once(':')?;
+            let slot = match key.trim().as_str() {
+                "part_one" => &mut answers.part_one,
+                "part_two" => &mut answers.part_two,
+                _ => return Err(key.trim().error("expected `part_one` or `part_two`")),
+            };
+            *slot = Some(value.trim().as_str().to_string());
+        }
+
+        Ok(answers)
+    }
+
+    pub fn for_part(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_one.as_deref(),
+            2 => self.part_two.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn set_part(&mut self, part: u8, answer: &str) {
+        match part {
+            1 => self.part_one = Some(answer.to_string()),
+            2 => self.part_two = Some(answer.to_string()),
+            _ => {}
+        }
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(part_one) = &self.part_one {
+            writeln!(f, "part_one: {part_one}")?;
+        }
+        if let Some(part_two) = &self.part_two {
+            writeln!(f, "part_two: {part_two}")?;
+        }
+        Ok(())
+    }
+}
+
+struct PartResult {
+    answer: Option<String>,
+    expected: Option<String>,
+    duration: Duration,
+}
+
+impl PartResult {
+    fn matches(&self) -> Option<bool> {
+        self.expected
+            .as_ref()
+            .map(|expected| self.answer.as_ref() == Some(expected))
+    }
+
+    fn answer_cell(&self) -> String {
+        let answer = self.answer.as_deref().unwrap_or("✖");
+        match (self.matches(), &self.expected) {
+            (Some(true), _) => format!("{answer} ✔"),
+            (Some(false), Some(expected)) => format!("{answer} ✘ (expected {expected})"),
+            _ => answer.to_string(),
+        }
+    }
+}
+
+/// The `*.txt` files in `dir`, sorted by name.
+pub(crate) fn input_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("could not read input directory \"{}\": {e}", dir.display()))?;
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    files.sort();
+
+    if files.is_empty() {
+        return Err(format!(
+            "no inputs found in \"{}\". Add one `<name>.txt` file per input.",
+            dir.display()
+        ));
+    }
+
+    Ok(files)
+}
+
+fn run_input(path: &Path, parts: &[(u8, Solver)]) -> Result<Vec<PartResult>, String> {
+    let input = fs::read_to_string(path)
+        .map_err(|e| format!("could not read input \"{}\": {e}", path.display()))?;
+
+    let answers_path = path.with_extension("answers");
+    let answers = match fs::read_to_string(&answers_path) {
+        Ok(text) => Answers::parse(&text)
+            .map_err(|e| format!("invalid answers in \"{}\": {e}", answers_path.display()))?,
+        Err(_) => Answers::default(),
+    };
+
+    Ok(parts
+        .iter()
+        .map(|(part, solve)| {
+            let timer = Instant::now();
+            let answer = solve(&input);
+            PartResult {
+                answer,
+                expected: answers.for_part(*part).map(str::to_string),
+                duration: timer.elapsed(),
+            }
+        })
+        .collect())
+}
+
+fn print_table(rows: &[Vec<String>]) {
+    let widths: Vec<usize> = (0..rows[0].len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for (index, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        println!("{}", cells.join(" | ").trim_end());
+
+        if index == 0 {
+            let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+            println!("{}", separator.join("-|-"));
+        }
+    }
+}
+
+/// Runs `parts` against every `*.txt` file in `dir` and prints a table of answers and timings.
+/// Exits with a non-zero status if any answer differs from its expected answer.
+pub fn run_inputs(day: Day, dir: &Path, parts: &[(u8, Solver)]) {
+    let files = input_files(dir).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        process::exit(1);
+    });
+
+    let mut header = vec!["Input".to_string()];
+    for (part, _) in parts {
+        header.extend([format!("Part {part}"), "Time".to_string()]);
+    }
+
+    let mut rows = vec![header];
+    let (mut checked, mut mismatched) = (0, 0);
+
+    for path in &files {
+        let name = path
+            .file_stem()
+            .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+
+        let results = run_input(path, parts).unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        });
+
+        let mut row = vec![name];
+        for result in &results {
+            row.extend([result.answer_cell(), format!("{:.1?}", result.duration)]);
+        }
+        rows.push(row);
+
+        let matches: Vec<bool> = results.iter().filter_map(PartResult::matches).collect();
+        if !matches.is_empty() {
+            checked += 1;
+            if matches.contains(&false) {
+                mismatched += 1;
+            }
+        }
+    }
+
+    println!(
+        "{ANSI_BOLD}Day {day}{ANSI_RESET} against {} inputs in \"{}\":",
+        files.len(),
+        dir.display()
+    );
+    println!();
+    print_table(&rows);
+    println!();
+
+    if checked == 0 {
+        println!("No expected answers found. Add `<name>.answers` files to check the results.");
+    } else if mismatched == 0 {
+        println!("All {checked} inputs with expected answers match. ✔");
+    } else {
+        println!("{mismatched} of {checked} inputs with expected answers do not match. ✘");
+        process::exit(1);
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Answers, PartResult};
+    use std::time::Duration;
+
+    #[test]
+    fn parses_answers() {
+        assert_eq!(
+            Answers::parse("part_one: 11\r\n\npart_two:31\n"),
+            Ok(Answers {
+                part_one: Some("11".into()),
+                part_two: Some("31".into()),
+            })
+        );
+        assert_eq!(
+            Answers::parse("part_two: ,,\n"),
+            Ok(Answers {
+                part_one: None,
+                part_two: Some(",,".into()),
+            })
+        );
+        assert_eq!(
+            Answers::parse("part_one: 1\npart_3: 2")
+                .unwrap_err()
+                .to_string(),
+            "line 2, column 1: expected `part_one` or `part_two`"
+        );
+    }
+
+    #[test]
+    fn compares_answers() {
+        let result = |answer: Option<&str>, expected: Option<&str>| PartResult {
+            answer: answer.map(str::to_string),
+            expected: expected.map(str::to_string),
+            duration: Duration::ZERO,
+        };
+
+        assert_eq!(result(Some("1"), None).answer_cell(), "1");
+        assert_eq!(result(Some("1"), Some("1")).answer_cell(), "1 ✔");
+        assert_eq!(
+            result(Some("1"), Some("2")).answer_cell(),
+            "1 ✘ (expected 2)"
+        );
+        assert_eq!(result(None, Some("2")).answer_cell(), "✖ ✘ (expected 2)");
+    }
+}
@@ -0,0 +1,226 @@
+//! Extracts examples and their expected answers from a puzzle description written by aoc-cli.
+//!
+//! Examples are the code blocks introduced by a paragraph containing "for example", answers are
+//! the last emphasised code span (`` `*42*` ``) of each part.
+
+use crate::template::Day;
+
+/// An expected answer and the (1-based) example it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub value: String,
+    pub example: usize,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PuzzleExamples {
+    /// The puzzle heading without its dashes, e.g. `Day 1: Historian Hysteria`.
+    pub title: Option<String>,
+    /// Distinct example inputs, in order of appearance.
+    pub examples: Vec<String>,
+    pub part_one: Option<Answer>,
+    pub part_two: Option<Answer>,
+}
+
+impl PuzzleExamples {
+    /// Name of the example file for the (1-based) `index`: `NN.txt`, `NN-2.txt`, ...
+    #[must_use]
+    pub fn file_name(day: Day, index: usize) -> String {
+        if index <= 1 {
+            format!("{day}.txt")
+        } else {
+            format!("{day}-{index}.txt")
+        }
+    }
+}
+
+/// The last emphasised code span in `line`, e.g. `` `*11*` ``.
+fn last_emphasised_code(line: &str) -> Option<&str> {
+    line.rmatch_indices("`*")
+        .find_map(|(start, _)| {
+            let rest = &line[start + 2..];
+            rest.find("*`").map(|end| &rest[..end])
+        })
+        .filter(|value| !value.is_empty())
+}
+
+/// The title in a heading like `\--- Day 1: Historian Hysteria ---`.
+fn heading_title(line: &str) -> Option<&str> {
+    let title = line
+        .trim()
+        .trim_start_matches('\\')
+        .strip_prefix("--- ")?
+        .strip_suffix(" ---")?;
+    title.starts_with("Day ").then_some(title)
+}
+
+#[must_use]
+pub fn extract(markdown: &str) -> PuzzleExamples {
+    let mut result = PuzzleExamples::default();
+    let mut code_blocks: Vec<String> = vec![];
+
+    let mut in_part_two = false;
+    let mut paragraph = String::new();
+    let mut paragraph_ended = false;
+    let mut block: Option<(String, bool)> = None;
+    // the most recent example, which answers that follow it refer to.
+    let mut current_example = 0;
+
+    for line in markdown.lines() {
+        if line.trim() == "```" {
+            match block.take() {
+                Some((text, is_example)) => {
+                    code_blocks.push(text.clone());
+                    if is_example {
+                        current_example = match result.examples.iter().position(|e| *e == text) {
+                            Some(index) => index + 1,
+                            None => {
+                                result.examples.push(text);
+                                result.examples.len()
+                            }
+                        };
+                    }
+                }
+                None => {
+                    let is_example = paragraph.to_lowercase().contains("for example");
+                    block = Some((String::new(), is_example));
+                }
+            }
+            paragraph.clear();
+            continue;
+        }
+
+        if let Some((text, _)) = block.as_mut() {
+            text.push_str(line);
+            text.push('\n');
+            continue;
+        }
+
+        if result.title.is_none() {
+            if let Some(title) = heading_title(line) {
+                result.title = Some(title.to_string());
+                continue;
+            }
+        }
+
+        if line.contains("--- Part Two ---") {
+            in_part_two = true;
+            paragraph.clear();
+            continue;
+        }
+
+        // keep the finished paragraph around until the next one starts, it introduces the block.
+        if line.trim().is_empty() {
+            paragraph_ended = true;
+            continue;
+        }
+        if paragraph_ended {
+            paragraph.clear();
+            paragraph_ended = false;
+        }
+
+        paragraph.push_str(line);
+        paragraph.push(' ');
+
+        if let Some(value) = last_emphasised_code(line) {
+            let answer = Some(Answer {
+                value: value.to_string(),
+                example: current_example.max(1),
+            });
+            if in_part_two {
+                result.part_two = answer;
+            } else {
+                result.part_one = answer;
+            }
+        }
+    }
+
+    if result.examples.is_empty() {
+        result.examples.extend(code_blocks.into_iter().take(1));
+    }
+
+    result
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{extract, Answer, PuzzleExamples};
+    use crate::day;
+
+    const PUZZLE: &str = "\\--- Day 1: Historian Hysteria ---
+----------
+
+The lists could look like this:
+
+```
+not an example
+```
+
+For example:
+
+```
+3   4
+4   3
+```
+
+In the example above, this is `2 + 1`, a total distance of `*11*`!
+
+Your puzzle answer was `1234`.
+
+\\--- Part Two ---
+----------
+
+Here are the same example lists again:
+
+```
+3   4
+4   3
+```
+
+So, for example, with a new list:
+
+```
+1   2
+```
+
+the *similarity score* would be `*31*`.
+";
+
+    #[test]
+    fn extracts_examples_and_answers() {
+        let result = extract(PUZZLE);
+        assert_eq!(result.title.as_deref(), Some("Day 1: Historian Hysteria"));
+        assert_eq!(result.examples, ["3   4\n4   3\n", "1   2\n"]);
+        assert_eq!(
+            result.part_one,
+            Some(Answer {
+                value: "11".into(),
+                example: 1
+            })
+        );
+        assert_eq!(
+            result.part_two,
+            Some(Answer {
+                value: "31".into(),
+                example: 2
+            })
+        );
+    }
+
+    #[test]
+    fn falls_back_to_first_code_block() {
+        let result = extract("Consider:\n\n```\n1\n```\n\nthen `*2*`.\n");
+        assert_eq!(result.examples, ["1\n"]);
+        assert_eq!(result.part_one.map(|a| a.value), Some("2".into()));
+        assert_eq!(result.part_two, None);
+        assert_eq!(extract("no blocks"), PuzzleExamples::default());
+    }
+
+    #[test]
+    fn names_example_files() {
+        assert_eq!(PuzzleExamples::file_name(day!(1), 1), "01.txt");
+        assert_eq!(PuzzleExamples::file_name(day!(1), 2), "01-2.txt");
+    }
+}
@@ -0,0 +1,595 @@
+//! Append-only history of benchmark runs, stored as one JSON object per line.
+//! Unlike the stored timings, runs are never overwritten, so changes in speed can be tracked over time.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tinyjson::JsonValue;
+
+use crate::template::timings::{PartTiming, Timing, Timings};
+use crate::template::{Day, ANSI_BOLD, ANSI_RESET};
+
+pub static HISTORY_FILE_PATH: &str = "./data/timings-history.jsonl";
+
+/// A single `cargo time` run.
+#[derive(Clone, Debug)]
+pub struct Run {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    /// Short git revision, suffixed with `-dirty` if there are uncommitted changes.
+    pub revision: Option<String>,
+    pub machine: String,
+    pub timings: Timings,
+}
+
+impl Run {
+    /// Records timings measured now, on this machine.
+    pub fn new(timings: Timings) -> Self {
+        Run {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+            revision: git_revision(),
+            machine: machine_id(),
+            timings,
+        }
+    }
+}
+
+fn git_revision() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let revision = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let is_dirty = Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=no"])
+        .output()
+        .is_ok_and(|output| !output.stdout.is_empty());
+
+    Some(if is_dirty {
+        format!("{revision}-dirty")
+    } else {
+        revision
+    })
+}
+
+/// Identifies the machine a run was benched on, so only comparable runs are compared.
+/// Can be set with the `AOC_MACHINE` environment variable, defaults to the hostname.
+pub fn machine_id() -> String {
+    env::var("AOC_MACHINE")
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .or_else(|| env::var("HOSTNAME").ok())
+        .or_else(|| env::var("COMPUTERNAME").ok())
+        .map(|machine| machine.trim().to_string())
+        .filter(|machine| !machine.is_empty())
+        .unwrap_or_else(|| "unknown".into())
+}
+
+/// All recorded runs, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    pub runs: Vec<Run>,
+}
+
+impl History {
+    /// Reads the history. If not present, returns an empty history.
+    pub fn read_from_file(path: &Path) -> Result<Self, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(History::default()),
+            Err(e) => {
+                return Err(format!(
+                    "could not read history from \"{}\": {e}",
+                    path.display()
+                ))
+            }
+        };
+
+        let mut runs = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                Run::try_from(line).map_err(|e| {
+                    format!(
+                        "could not read history from \"{}\", line {}: {e}",
+                        path.display(),
+                        index + 1
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        runs.sort_by_key(|run| run.timestamp);
+        Ok(History { runs })
+    }
+
+    /// Appends a run to the history file, creating it if needed.
+    pub fn append(path: &Path, run: &Run) -> Result<(), String> {
+        let error = |e| format!("could not append to history \"{}\": {e}", path.display());
+        let line = JsonValue::from(run)
+            .stringify()
+            .expect("runs are always valid JSON.");
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(error)?;
+        writeln!(file, "{line}").map_err(error)
+    }
+
+    /// Compares each part of `current` against the latest earlier run of that part on `machine`.
+    pub fn compare(&self, current: &Timings, machine: &str) -> Vec<Comparison> {
+        let mut comparisons = vec![];
+
+        for timing in &current.data {
+            for (part, current) in parts(timing) {
+                let baseline = self
+                    .runs
+                    .iter()
+                    .rev()
+                    .filter(|run| run.machine == machine)
+                    .find_map(|run| Some((run, find_part(&run.timings, timing.day, part)?)));
+
+                if let Some((run, baseline)) = baseline {
+                    // medians are less affected by outliers, but only comparable to medians.
+                    let (baseline, current) = match (baseline.median_nanos, current.median_nanos) {
+                        (Some(baseline), Some(current)) => (baseline, current),
+                        _ => (baseline.nanos, current.nanos),
+                    };
+                    comparisons.push(Comparison {
+                        day: timing.day,
+                        part,
+                        baseline,
+                        current,
+                        revision: run.revision.clone(),
+                    });
+                }
+            }
+        }
+
+        comparisons
+    }
+
+    /// Median of each part over time, for runs on `machine`.
+    /// Days without recorded runs are left out.
+    pub fn trends(&self, machine: &str, days: &[Day]) -> Vec<Trend> {
+        let mut trends = vec![];
+
+        for &day in days {
+            for part in [1, 2] {
+                let points: Vec<(u64, f64)> = self
+                    .runs
+                    .iter()
+                    .filter(|run| run.machine == machine)
+                    .filter_map(|run| {
+                        let timing = find_part(&run.timings, day, part)?;
+                        Some((run.timestamp, timing.median_or_mean()))
+                    })
+                    .collect();
+
+                if !points.is_empty() {
+                    trends.push(Trend { day, part, points });
+                }
+            }
+        }
+
+        trends
+    }
+}
+
+fn parts(timing: &Timing) -> impl Iterator<Item = (u8, &PartTiming)> {
+    [(1, &timing.part_1), (2, &timing.part_2)]
+        .into_iter()
+        .filter_map(|(part, timing)| Some((part, timing.as_ref()?)))
+}
+
+fn find_part(timings: &Timings, day: Day, part: u8) -> Option<&PartTiming> {
+    let timing = timings.data.iter().find(|timing| timing.day == day)?;
+    parts(timing)
+        .find(|(p, _)| *p == part)
+        .map(|(_, timing)| timing)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The median of a part in the current run, compared to the latest recorded run.
+/// Falls back to the means if either run has no median.
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub day: Day,
+    pub part: u8,
+    pub baseline: f64,
+    pub current: f64,
+    /// Revision of the run used as baseline.
+    pub revision: Option<String>,
+}
+
+impl Comparison {
+    /// Relative change, e.g. `0.25` if the part got 25% slower.
+    /// `None` if the baseline is zero, as there is nothing to relate the change to.
+    pub fn change(&self) -> Option<f64> {
+        relative_change(self.baseline, self.current)
+    }
+
+    /// Whether the part got slower by more than `threshold`, e.g. `0.1` for 10%.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change().is_some_and(|change| change > threshold)
+    }
+}
+
+/// Prints a table of comparisons, flagging regressions. Returns the number of regressions.
+pub fn print_comparisons(comparisons: &[Comparison], threshold: f64) -> usize {
+    if comparisons.is_empty() {
+        println!("No earlier runs on this machine to compare against.");
+        return 0;
+    }
+
+    println!("{ANSI_BOLD}Compared to the latest recorded runs:{ANSI_RESET}");
+    for comparison in comparisons {
+        let flag = if comparison.is_regression(threshold) {
+            "✘ regressed"
+        } else {
+            "✔"
+        };
+        println!(
+            "Day {} Part {}: {} → {} ({}, since {}) {flag}",
+            comparison.day,
+            comparison.part,
+            format_nanos(comparison.baseline),
+            format_nanos(comparison.current),
+            format_change(comparison.change()),
+            comparison.revision.as_deref().unwrap_or("unknown revision"),
+        );
+    }
+
+    comparisons
+        .iter()
+        .filter(|comparison| comparison.is_regression(threshold))
+        .count()
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The median of a part over time.
+#[derive(Clone, Debug)]
+pub struct Trend {
+    pub day: Day,
+    pub part: u8,
+    /// Timestamp and median of each run, oldest first.
+    pub points: Vec<(u64, f64)>,
+}
+
+impl Trend {
+    fn first(&self) -> f64 {
+        self.points[0].1
+    }
+
+    fn latest(&self) -> f64 {
+        self.points[self.points.len() - 1].1
+    }
+
+    fn best(&self) -> f64 {
+        self.points
+            .iter()
+            .map(|(_, nanos)| *nanos)
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// One bar per run, scaled between the fastest and slowest run.
+    fn sparkline(&self) -> String {
+        const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let worst = self
+            .points
+            .iter()
+            .map(|(_, nanos)| *nanos)
+            .fold(0.0, f64::max);
+        let range = worst - self.best();
+
+        self.points
+            .iter()
+            .map(|(_, nanos)| {
+                if range == 0.0 {
+                    return BARS[0];
+                }
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let level = ((nanos - self.best()) / range * 7.0).round() as usize;
+                BARS[level]
+            })
+            .collect()
+    }
+
+    /// e.g. `Day 09 Part 1: 1.2ms → 800.0µs (-33.3%), best 790.0µs over 4 runs since 2024-12-09 ▇▃▁▁`
+    pub fn summary(&self) -> String {
+        format!(
+            "Day {} Part {}: {} → {} ({}), best {} over {} runs since {} {}",
+            self.day,
+            self.part,
+            format_nanos(self.first()),
+            format_nanos(self.latest()),
+            format_change(relative_change(self.first(), self.latest())),
+            format_nanos(self.best()),
+            self.points.len(),
+            format_date(self.points[0].0),
+            self.sparkline()
+        )
+    }
+}
+
+/// Change from `baseline` to `current` relative to `baseline`, `None` if the baseline is zero.
+fn relative_change(baseline: f64, current: f64) -> Option<f64> {
+    (baseline > 0.0).then(|| (current - baseline) / baseline)
+}
+
+/// e.g. `+25.0%`, or `n/a` if there is no change to show.
+fn format_change(change: Option<f64>) -> String {
+    change.map_or("n/a".into(), |change| format!("{:+.1}%", change * 100.0))
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_nanos(nanos: f64) -> String {
+    format!("{:.1?}", Duration::from_nanos(nanos as u64))
+}
+
+/// Formats a unix timestamp as `YYYY-MM-DD` (UTC).
+fn format_date(timestamp: u64) -> String {
+    // see: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = timestamp / 86_400 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Run> for JsonValue {
+    fn from(value: &Run) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(value.timestamp as f64),
+        );
+        map.insert(
+            "revision".into(),
+            value
+                .revision
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+        map.insert("machine".into(), JsonValue::String(value.machine.clone()));
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.timings.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&str> for Run {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(value).or(Err("not valid JSON."))?;
+        let run = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected run to be an object.")?;
+
+        let timestamp = run
+            .get("timestamp")
+            .and_then(|v| v.get::<f64>().copied())
+            .ok_or("expected run.timestamp to be a number.")?;
+
+        let revision = run
+            .get("revision")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("expected run.revision to be null or a string.")?;
+
+        let machine = run
+            .get("machine")
+            .and_then(|v| v.get::<String>())
+            .ok_or("expected run.machine to be a string.")?;
+
+        let data = run
+            .get("data")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("expected run.data to be an array.")?
+            .iter()
+            .map(Timing::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Run {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            timestamp: timestamp as u64,
+            revision: revision.cloned(),
+            machine: machine.clone(),
+            timings: Timings { data },
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{env, fs};
+
+    use super::{format_date, Comparison, History, Run};
+    use crate::day;
+    use crate::template::timings::{PartTiming, Timing, Timings};
+
+    fn run(timestamp: u64, machine: &str, part_1: &str, part_2: Option<&str>) -> Run {
+        Run {
+            timestamp,
+            revision: Some(format!("rev{timestamp}")),
+            machine: machine.into(),
+            timings: Timings {
+                data: vec![Timing {
+                    day: day!(9),
+                    parse: None,
+                    part_1: PartTiming::from_duration(part_1, Some(100)),
+                    part_2: part_2.and_then(|part_2| PartTiming::from_duration(part_2, Some(100))),
+                    total_nanos: 0.0,
+                    timed_out_nanos: None,
+                }],
+            },
+        }
+    }
+
+    #[test]
+    fn appends_and_reads_runs() {
+        let path = env::temp_dir().join(format!("aoc-history-{}.jsonl", std::process::id()));
+        History::append(&path, &run(2, "box", "2ms", None)).unwrap();
+        History::append(&path, &run(1, "box", "1ms", Some("3ms"))).unwrap();
+        let history = History::read_from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(history.runs.len(), 2);
+        assert_eq!(history.runs[0].timestamp, 1);
+        assert_eq!(history.runs[0].revision.as_deref(), Some("rev1"));
+        assert_eq!(
+            history.runs[0].timings.data[0].part_2,
+            PartTiming::from_duration("3ms", Some(100))
+        );
+    }
+
+    #[test]
+    fn reports_corrupt_lines() {
+        let path =
+            env::temp_dir().join(format!("aoc-history-corrupt-{}.jsonl", std::process::id()));
+        fs::write(&path, "{ \"timestamp\": 1, \"revision\": null }\n").unwrap();
+        let error = History::read_from_file(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(
+            error.ends_with("line 1: expected run.machine to be a string."),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn compares_against_latest_run_on_same_machine() {
+        let history = History {
+            runs: vec![
+                run(1, "box", "1ms", Some("10ms")),
+                run(2, "box", "2ms", None),
+                run(3, "laptop", "9ms", Some("1ms")),
+            ],
+        };
+        let current = run(4, "box", "2.5ms", Some("10.5ms")).timings;
+        let comparisons = history.compare(&current, "box");
+
+        assert_eq!(comparisons.len(), 2);
+        assert_eq!(comparisons[0].baseline, 2_000_000.0);
+        assert_eq!(comparisons[0].revision.as_deref(), Some("rev2"));
+        assert!(comparisons[0].is_regression(0.1));
+        assert_eq!(comparisons[1].baseline, 10_000_000.0);
+        assert!(!comparisons[1].is_regression(0.1));
+    }
+
+    #[test]
+    fn compares_medians_if_known() {
+        let mut previous = run(1, "box", "2ms", None);
+        previous.timings.data[0].part_1 = previous.timings.data[0]
+            .part_1
+            .take()
+            .map(|part| part.with_median("1ms", "10µs"));
+        let history = History {
+            runs: vec![previous],
+        };
+
+        let mut current = run(2, "box", "1.5ms", None).timings;
+        let comparisons = history.compare(&current, "box");
+        assert_eq!(
+            (comparisons[0].baseline, comparisons[0].current),
+            (2_000_000.0, 1_500_000.0),
+            "means are compared if the current run has no median."
+        );
+
+        current.data[0].part_1 = current.data[0]
+            .part_1
+            .take()
+            .map(|part| part.with_median("1.05ms", "10µs"));
+        let comparisons = history.compare(&current, "box");
+        assert_eq!(comparisons[0].baseline, 1_000_000.0);
+        assert!(!comparisons[0].is_regression(0.1));
+    }
+
+    #[test]
+    fn ignores_changes_from_zero() {
+        let comparison = Comparison {
+            day: day!(9),
+            part: 1,
+            baseline: 0.0,
+            current: 10.0,
+            revision: None,
+        };
+        assert_eq!(comparison.change(), None);
+        assert!(!comparison.is_regression(0.1));
+    }
+
+    #[test]
+    fn summarises_trends() {
+        let history = History {
+            runs: vec![
+                run(1_733_702_400, "box", "4ms", None),
+                run(1_733_788_800, "box", "1ms", None),
+                run(1_733_875_200, "box", "2ms", None),
+            ],
+        };
+        let trends = history.trends("box", &[day!(9), day!(10)]);
+
+        assert_eq!(trends.len(), 1);
+        assert_eq!(
+            trends[0].summary(),
+            "Day 09 Part 1: 4.0ms → 2.0ms (-50.0%), best 1.0ms over 3 runs since 2024-12-09 █▁▃"
+        );
+    }
+
+    #[test]
+    fn summarises_trends_from_zero() {
+        let history = History {
+            runs: vec![
+                run(1_733_702_400, "box", "0ns", None),
+                run(1_733_788_800, "box", "1ms", None),
+            ],
+        };
+        let trends = history.trends("box", &[day!(9)]);
+
+        assert_eq!(
+            trends[0].summary(),
+            "Day 09 Part 1: 0.0ns → 1.0ms (n/a), best 0.0ns over 2 runs since 2024-12-09 ▁█"
+        );
+    }
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_733_011_199), "2024-11-30");
+    }
+}
@@ -0,0 +1,225 @@
+//! Selects and reads the input a solution is run against.
+
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::{env, fs, process};
+
+use crate::template::{data_path, Day};
+
+/// Where a solution reads its input from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum InputSource {
+    /// The downloaded puzzle input at `data/inputs/<day>.txt`.
+    #[default]
+    Puzzle,
+    /// The example at `data/examples/<day>.txt`, or `<day>-<n>.txt` for further examples.
+    Example(Option<u8>),
+    /// An arbitrary file, passed with `--input <path>`.
+    File(PathBuf),
+    /// Standard input, selected with `-`.
+    Stdin,
+    /// Every `*.txt` file in a directory, compared side by side. Selected with `--input <dir>`,
+    /// or `--inputs` for `data/inputs/<day>/`.
+    Directory(Option<PathBuf>),
+}
+
+impl InputSource {
+    /// Removes `--input <path>`, `--example [n]` and `-` from `args` and returns the source they select.
+    pub fn extract(args: &mut Vec<String>) -> Result<Self, String> {
+        let mut sources = vec![];
+        let mut index = 0;
+
+        while index < args.len() {
+            match args[index].as_str() {
+                "--input" => {
+                    args.remove(index);
+                    if index >= args.len() {
+                        return Err("missing path for `--input`.".into());
+                    }
+                    let path = PathBuf::from(args.remove(index));
+                    sources.push(if path.is_dir() {
+                        InputSource::Directory(Some(path))
+                    } else {
+                        InputSource::File(path)
+                    });
+                }
+                "--example" => {
+                    args.remove(index);
+                    let part = args.get(index).and_then(|arg| arg.parse::<u8>().ok());
+                    if part.is_some() {
+                        args.remove(index);
+                    }
+                    sources.push(InputSource::Example(part));
+                }
+                "--inputs" => {
+                    args.remove(index);
+                    sources.push(InputSource::Directory(None));
+                }
+                "-" => {
+                    args.remove(index);
+                    sources.push(InputSource::Stdin);
+                }
+                _ => index += 1,
+            }
+        }
+
+        match sources.len() {
+            0 => Ok(InputSource::Puzzle),
+            1 => Ok(sources.remove(0)),
+            _ => Err(
+                "only one of `--input <path>`, `--inputs`, `--example [n]` or `-` can be used."
+                    .into(),
+            ),
+        }
+    }
+
+    /// Reads the input source from the arguments passed to the binary, exiting on invalid input.
+    pub fn from_args() -> Self {
+        let mut args: Vec<String> = env::args().skip(1).collect();
+        Self::extract(&mut args).unwrap_or_else(|e| {
+            eprintln!("Unexpected command-line input: {e}");
+            process::exit(1);
+        })
+    }
+
+    /// The arguments that select this source when passed to a solution binary.
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            InputSource::Puzzle => vec![],
+            InputSource::Example(None) => vec!["--example".into()],
+            InputSource::Example(Some(part)) => vec!["--example".into(), part.to_string()],
+            InputSource::File(path) => vec!["--input".into(), path.display().to_string()],
+            InputSource::Stdin => vec!["-".into()],
+            InputSource::Directory(None) => vec!["--inputs".into()],
+            InputSource::Directory(Some(path)) => {
+                vec!["--input".into(), path.display().to_string()]
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn is_example(&self) -> bool {
+        matches!(self, InputSource::Example(_))
+    }
+
+    /// The directory of inputs to compare, if this source selects one.
+    #[must_use]
+    pub fn directory(&self, day: Day) -> Option<PathBuf> {
+        match self {
+            InputSource::Directory(Some(path)) => Some(path.clone()),
+            InputSource::Directory(None) => Some(data_path("inputs", &day.to_string())),
+            _ => None,
+        }
+    }
+
+    /// Reads the selected input, describing how to fix a missing file in the error.
+    pub fn read(&self, day: Day) -> Result<String, String> {
+        let path = match self {
+            InputSource::Stdin => {
+                let mut input = String::new();
+                return io::stdin()
+                    .read_to_string(&mut input)
+                    .map(|_| input)
+                    .map_err(|e| format!("could not read input from stdin: {e}"));
+            }
+            InputSource::Puzzle => data_path("inputs", &format!("{day}.txt")),
+            InputSource::Example(None) => data_path("examples", &format!("{day}.txt")),
+            InputSource::Example(Some(part)) => data_path("examples", &format!("{day}-{part}.txt")),
+            InputSource::File(path) => path.clone(),
+            InputSource::Directory(_) => {
+                return Err("a directory of inputs can't be read as a single input.".into())
+            }
+        };
+
+        fs::read_to_string(&path).map_err(|e| {
+            let hint = match self {
+                InputSource::Puzzle => format!(
+                    "\nRun `cargo download {day}` to fetch it, or pass `--input <path>` or `--example` to use a different input."
+                ),
+                InputSource::Example(_) => format!(
+                    "\nPaste the example from the puzzle description into it, e.g. with `cargo read {day}`."
+                ),
+                _ => String::new(),
+            };
+            format!("could not open input file \"{}\": {e}.{hint}", path.display())
+        })
+    }
+
+    /// Reads the selected input, exiting with a helpful message if it is not available.
+    #[must_use]
+    pub fn read_or_exit(&self, day: Day) -> String {
+        self.read(day).unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::InputSource;
+
+    fn extract(args: &[&str]) -> (Result<InputSource, String>, Vec<String>) {
+        let mut args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        let source = InputSource::extract(&mut args);
+        (source, args)
+    }
+
+    #[test]
+    fn defaults_to_puzzle_input() {
+        let (source, rest) = extract(&["--time"]);
+        assert_eq!(source, Ok(InputSource::Puzzle));
+        assert_eq!(rest, ["--time"]);
+    }
+
+    #[test]
+    fn extracts_examples() {
+        let (source, rest) = extract(&["--example", "--submit", "1"]);
+        assert_eq!(source, Ok(InputSource::Example(None)));
+        assert_eq!(rest, ["--submit", "1"]);
+
+        let (source, rest) = extract(&["--example", "2", "--time"]);
+        assert_eq!(source, Ok(InputSource::Example(Some(2))));
+        assert_eq!(rest, ["--time"]);
+    }
+
+    #[test]
+    fn extracts_files_and_stdin() {
+        let (source, rest) = extract(&["--input", "other.txt"]);
+        assert_eq!(source, Ok(InputSource::File("other.txt".into())));
+        assert!(rest.is_empty());
+
+        let (source, _) = extract(&["-"]);
+        assert_eq!(source, Ok(InputSource::Stdin));
+
+        let (source, _) = extract(&["--input", "src"]);
+        assert_eq!(source, Ok(InputSource::Directory(Some("src".into()))));
+
+        assert!(extract(&["--input"]).0.is_err());
+        assert!(extract(&["-", "--example"]).0.is_err());
+    }
+
+    #[test]
+    fn round_trips_arguments() {
+        for source in [
+            InputSource::Puzzle,
+            InputSource::Example(None),
+            InputSource::Example(Some(3)),
+            InputSource::File("a/b.txt".into()),
+            InputSource::Stdin,
+            InputSource::Directory(None),
+        ] {
+            let (parsed, _) = extract(
+                &source
+                    .to_args()
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>(),
+            );
+            assert_eq!(parsed, Ok(source));
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Local ledger of answers accepted by adventofcode.com, one file per day in `data/answers`.
+//! Files use the same format as the expected answers of `--inputs`, so they can be written by hand as well.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use crate::template::compare::Answers;
+use crate::template::Day;
+
+static LEDGER_DIR: &str = "data/answers";
+
+fn ledger_path(day: Day) -> PathBuf {
+    PathBuf::from(LEDGER_DIR).join(format!("{day}.answers"))
+}
+
+/// Accepted answers of a day. If none were recorded, returns empty answers.
+pub fn read(day: Day) -> Result<Answers, String> {
+    let path = ledger_path(day);
+    match fs::read_to_string(&path) {
+        Ok(text) => Answers::parse(&text)
+            .map_err(|e| format!("invalid answers in \"{}\": {e}", path.display())),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Answers::default()),
+        Err(e) => Err(format!("could not read \"{}\": {e}", path.display())),
+    }
+}
+
+/// Records an accepted answer, keeping the other part's answer.
+pub fn record(day: Day, part: u8, answer: &str) -> Result<(), String> {
+    let mut answers = read(day)?;
+    answers.set_part(part, answer);
+
+    let path = ledger_path(day);
+    fs::create_dir_all(LEDGER_DIR)
+        .and_then(|()| fs::write(&path, answers.to_string()))
+        .map_err(|e| format!("could not write \"{}\": {e}", path.display()))
+}
@@ -1,13 +1,29 @@
+use std::path::PathBuf;
 use std::{env, fs};
 
+pub mod alloc;
 pub mod aoc_cli;
 pub mod commands;
+pub mod compare;
+pub mod examples;
+pub mod input;
+pub mod params;
+pub mod perf;
 pub mod runner;
+pub mod validate;
 
 pub use day::*;
+pub use history::HISTORY_FILE_PATH;
+pub use report::ReportFormat;
+pub use run_multi::RunOptions;
+pub use timings::{parse_duration, TIMINGS_FILE_PATH};
 
 mod day;
+mod history;
+mod ledger;
 mod readme_benchmarks;
+mod readme_stars;
+mod report;
 mod run_multi;
 mod timings;
 
@@ -15,11 +31,16 @@ pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
+/// Path of a file in the `data` directory.
+fn data_path(folder: &str, file_name: &str) -> PathBuf {
+    let cwd = env::current_dir().unwrap();
+    cwd.join("data").join(folder).join(file_name)
+}
+
 /// Helper function that reads a text file to a string.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = data_path(folder, &format!("{day}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
@@ -27,11 +48,7 @@ pub fn read_file(folder: &str, day: Day) -> String {
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
-        .join(format!("{day}-{part}.txt"));
+    let filepath = data_path(folder, &format!("{day}-{part}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
@@ -39,19 +56,129 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// The generated `main` reads `data/inputs/<day>.txt` unless `--input <path>`, `--example [n]`
+/// or `-` (stdin) select a different input. `--inputs` (or `--input <dir>`) runs the solution
+/// against every input in `data/inputs/<day>/` and prints a comparison table.
+///
+/// Passing `params = <type>` (see [`params!`](crate::params)) runs the parts with the parameters
+/// for the real input, overridable via `--param <name>=<value>`. Parts then take the parameters
+/// as a second argument: `fn part_one(input: &str, params: &Params)`.
+///
+/// Passing `parse = <fn>` parses the input once with `fn parse(input: &str) -> Option<T>`, timed
+/// separately from the parts. Parts then take the parsed value: `fn part_one(input: &T)`.
+/// It goes before `params`, e.g. `solution!(14, parse = parse, params = Params)`.
+///
+/// Passing `validate = <fn>` checks the input with `fn validate(input: &mut Validator)` (see
+/// [`validate`](crate::template::validate)) before solving, stopping if it reports errors. With
+/// `--check`, the diagnostics are printed instead of solving. It goes after `params`.
+///
+/// Passing `variants = [part_one => [part_one_backward]]` runs alternative implementations of a
+/// part after it. They take the same arguments as the part, must agree on its answer and are
+/// benched next to it, printing their speedup. It goes last, e.g. `solution!(7, variants = [...])`.
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+    ($day:expr $(, $($options:tt)*)?) => {
+        $crate::solution!(@parts $day, [$($($options)*)?]);
+    };
+
+    // selects the parts to run, `1` or `2` run a single one.
+    (@parts $day:expr, [1 $(, $($options:tt)*)?]) => {
+        $crate::solution!(@options $day, [[part_one, 1]] $(, $($options)*)?);
+    };
+    (@parts $day:expr, [2 $(, $($options:tt)*)?]) => {
+        $crate::solution!(@options $day, [[part_two, 2]] $(, $($options)*)?);
+    };
+    (@parts $day:expr, []) => {
+        $crate::solution!(@options $day, [[part_one, 1] [part_two, 2]]);
+    };
+    (@parts $day:expr, [$($options:tt)+]) => {
+        $crate::solution!(@options $day, [[part_one, 1] [part_two, 2]], $($options)+);
+    };
+
+    // normalises the optional keys, so a single `@impl` covers every combination of them.
+    (@options $day:expr, $parts:tt $(, parse = $parse:expr)? $(, params = $params:ty)? $(, validate = $validate:expr)? $(, variants = $variants:tt)? $(,)?) => {
+        $crate::solution!(@impl $day, $parts, parse [$($parse)?], params [$($params)?], validate [$($validate)?], variants [$($variants)?]);
+    };
+
+    (@impl $day:expr, [$([$func:expr, $part:expr])*], parse $parse:tt, params $params:tt, validate $validate:tt, variants [$([$($base:ident => [$($variant:ident),+ $(,)?]),+ $(,)?])?]) => {
+        $crate::solution!(@setup $day);
+
+        fn main() {
+            use $crate::template::runner::*;
+            let source = $crate::template::input::InputSource::from_args();
+            $crate::solution!(@params params, source, $params);
+            if let Some(dir) = source.directory(DAY) {
+                run_check_inputs(&dir, DAY, $crate::solution!(@validator $validate));
+                use $crate::template::compare::{run_inputs, solver};
+                run_inputs(DAY, &dir, &[$( ($part, solver($crate::solution!(@solver $func, $parse, $params params))) ),*]);
+                return;
+            }
+            let input = source.read_or_exit(DAY);
+            run_check(&input, DAY, $crate::solution!(@validator $validate));
+            let parsed = $crate::solution!(@parse $parse, input);
+            $( run_part(|parsed| $crate::solution!(@call $func, parsed, $params params), parsed, DAY, $part); )*
+            $(
+                let agree = [$(
+                    run_variants(stringify!($base), parsed, &[
+                        (stringify!($base), &|parsed| $crate::solution!(@call $base, parsed, $params params)),
+                        $( (stringify!($variant), &|parsed| $crate::solution!(@call $variant, parsed, $params params)), )+
+                    ]),
+                )+];
+                if agree.contains(&false) {
+                    std::process::exit(1);
+                }
+            )?
+        }
+    };
+
+    // declares `$name` with the parameters for the selected input, overridden by `--param`.
+    (@params $name:ident, $source:ident, []) => {};
+    (@params $name:ident, $source:ident, [$params:ty]) => {
+        let $name = $crate::template::params::apply_args(if $source.is_example() {
+            <$params as $crate::template::params::Params>::example()
+        } else {
+            <$params as $crate::template::params::Params>::input()
+        });
+    };
+
+    // what the parts are called with: the parsed input, or the input itself without `parse`.
+    (@parse [], $input:ident) => {
+        $input.as_str()
+    };
+    (@parse [$parse:expr], $input:ident) => {
+        &match $crate::template::runner::run_parse($parse, $input.as_str()) {
+            Some(parsed) => parsed,
+            None => {
+                eprintln!("Error: could not parse the input.");
+                std::process::exit(1);
+            }
+        }
+    };
+
+    (@call $func:expr, $arg:expr, [] $name:ident) => {
+        $func($arg)
+    };
+    (@call $func:expr, $arg:expr, [$params:ty] $name:ident) => {
+        $func($arg, &$name)
+    };
+
+    // a part as a function of the raw input, for runs against a directory of inputs.
+    (@solver $func:expr, [], $params:tt $name:ident) => {
+        |input: &str| $crate::solution!(@call $func, input, $params $name)
+    };
+    (@solver $func:expr, [$parse:expr], $params:tt $name:ident) => {
+        |input: &str| $parse(input).and_then(|parsed| $crate::solution!(@call $func, &parsed, $params $name))
     };
-    ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+
+    (@validator []) => {
+        |_: &mut $crate::template::validate::Validator| {}
     };
-    ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+    (@validator [$validate:expr]) => {
+        $validate
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@setup $day:expr) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -59,10 +186,8 @@ macro_rules! solution {
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
-        fn main() {
-            use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
-        }
+        #[cfg(all(feature = "count-allocations", not(feature = "dhat-heap")))]
+        #[global_allocator]
+        static ALLOC: $crate::template::alloc::CountingAlloc = $crate::template::alloc::CountingAlloc;
     };
 }
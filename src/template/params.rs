@@ -0,0 +1,143 @@
+//! Per-day parameters for constants that differ between the example and the real input,
+//! e.g. grid dimensions or iteration counts.
+
+use std::{env, process};
+
+/// A set of named parameters a solution can be run with.
+///
+/// Usually implemented through the [`params!`](crate::params) macro.
+pub trait Params: Sized {
+    /// Parameters for the real puzzle input.
+    fn input() -> Self;
+
+    /// Parameters for the example input.
+    fn example() -> Self;
+
+    /// Overrides the parameter `name` with the parsed `value`.
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String>;
+}
+
+/// Applies all `--param <name>=<value>` arguments passed to the binary on top of `params`.
+pub fn apply_args<P: Params>(mut params: P) -> P {
+    let args: Vec<String> = env::args().collect();
+
+    for pair in args
+        .windows(2)
+        .filter(|window| window[0] == "--param")
+        .map(|window| &window[1])
+    {
+        let Some((name, value)) = pair.split_once('=') else {
+            eprintln!("Unexpected command-line input. Format: --param <name>=<value>");
+            process::exit(1);
+        };
+
+        if let Err(e) = params.set(name.trim(), value.trim()) {
+            eprintln!("Invalid parameter: {e}");
+            process::exit(1);
+        }
+    }
+
+    params
+}
+
+/// Declares a parameter struct with separate defaults for the real input and the example.
+///
+/// Every field needs to implement [`FromStr`](std::str::FromStr) so it can be overridden
+/// from the command-line via `--param <name>=<value>`.
+///
+/// ```
+/// advent_of_code::params! {
+///     pub struct Params {
+///         columns: u64 = { input: 101, example: 11 },
+///         rows: u64 = { input: 103, example: 7 },
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! params {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_meta:meta])*
+                $field:ident : $ty:ty = { input: $input:expr, example: $example:expr $(,)? }
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq)]
+        $vis struct $name {
+            $(
+                $(#[$field_meta])*
+                pub $field: $ty,
+            )*
+        }
+
+        impl $crate::template::params::Params for $name {
+            fn input() -> Self {
+                Self { $( $field: $input, )* }
+            }
+
+            fn example() -> Self {
+                Self { $( $field: $example, )* }
+            }
+
+            fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+                $(
+                    if name == stringify!($field) {
+                        self.$field = value.parse().map_err(|_| {
+                            format!("could not parse `{value}` for `{name}`")
+                        })?;
+                        return Ok(());
+                    }
+                )*
+                Err(format!(
+                    "unknown parameter `{name}`, expected one of: {}",
+                    [$( stringify!($field) ),*].join(", ")
+                ))
+            }
+        }
+    };
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Params as _;
+
+    crate::params! {
+        struct Params {
+            columns: u64 = { input: 101, example: 11 },
+            blinks: usize = { input: 75, example: 6 },
+        }
+    }
+
+    #[test]
+    fn uses_separate_defaults() {
+        assert_eq!(Params::input().columns, 101);
+        assert_eq!(Params::example().columns, 11);
+        assert_eq!(Params::example().blinks, 6);
+    }
+
+    #[test]
+    fn overrides_parameters() {
+        let mut params = Params::example();
+        params.set("blinks", "25").unwrap();
+        assert_eq!(params.blinks, 25);
+        assert_eq!(params.columns, 11);
+    }
+
+    #[test]
+    fn rejects_unknown_parameters() {
+        let mut params = Params::input();
+        assert_eq!(
+            params.set("rows", "7"),
+            Err("unknown parameter `rows`, expected one of: columns, blinks".into())
+        );
+        assert_eq!(
+            params.set("columns", "-1"),
+            Err("could not parse `-1` for `columns`".into())
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! Hardware performance counters read with `perf_event_open`, enabled with the `perf-counters` feature on Linux.
+//! The runner counts each run of a part, so slow parts can be told apart by what makes them slow,
+//! e.g. many instructions versus many cache misses. Without the feature, or where the kernel or CPU
+//! do not expose the counters, opening them fails and the runner only measures wall-clock time.
+
+use std::fmt::Display;
+use std::io;
+
+/// A hardware event counted around each run of a part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    Instructions,
+    Cycles,
+    CacheMisses,
+    BranchMisses,
+}
+
+impl Event {
+    pub const ALL: [Event; 4] = [
+        Event::Instructions,
+        Event::Cycles,
+        Event::CacheMisses,
+        Event::BranchMisses,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Event::Instructions => "instructions",
+            Event::Cycles => "cycles",
+            Event::CacheMisses => "cache misses",
+            Event::BranchMisses => "branch misses",
+        }
+    }
+
+    /// The `PERF_COUNT_HW_*` id of the event.
+    #[cfg_attr(
+        not(all(feature = "perf-counters", target_os = "linux")),
+        allow(dead_code)
+    )]
+    fn config(self) -> u64 {
+        match self {
+            Event::Cycles => 0,
+            Event::Instructions => 1,
+            Event::CacheMisses => 3,
+            Event::BranchMisses => 5,
+        }
+    }
+}
+
+/// Open counters for every event the system supports.
+pub struct Counters {
+    counters: Vec<(Event, sys::Counter)>,
+    totals: Vec<u64>,
+    runs: u64,
+}
+
+impl Counters {
+    /// Fails if none of the events can be counted.
+    pub fn open() -> Result<Self, String> {
+        let mut counters = vec![];
+        let mut error = None;
+
+        for event in Event::ALL {
+            match sys::Counter::open(event.config()) {
+                Ok(counter) => counters.push((event, counter)),
+                Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                    error.get_or_insert(format!(
+                        "could not count {}: {e}. Lower `/proc/sys/kernel/perf_event_paranoid` to allow counting.",
+                        event.name()
+                    ));
+                }
+                // the kernel reports events without hardware support as missing.
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::NotFound | io::ErrorKind::Unsupported
+                    ) =>
+                {
+                    error.get_or_insert(format!(
+                        "{} are not supported by this CPU or kernel ({e}).",
+                        event.name()
+                    ));
+                }
+                Err(e) => {
+                    error.get_or_insert(format!("could not count {}: {e}", event.name()));
+                }
+            }
+        }
+
+        if counters.is_empty() {
+            return Err(error.unwrap_or_else(|| "no hardware counters available.".into()));
+        }
+
+        Ok(Counters {
+            totals: vec![0; counters.len()],
+            counters,
+            runs: 0,
+        })
+    }
+
+    /// Runs `func`, adding the events it caused to the totals.
+    /// A counter that fails to read is dropped, so a flaky event does not skew the others.
+    pub fn measure<T>(&mut self, func: impl FnOnce() -> T) -> T {
+        let mut failed = vec![];
+
+        for (i, (_, counter)) in self.counters.iter().enumerate() {
+            if counter.start().is_err() {
+                failed.push(i);
+            }
+        }
+
+        let result = func();
+
+        for (i, (_, counter)) in self.counters.iter_mut().enumerate() {
+            match counter.stop() {
+                Ok(count) => self.totals[i] += count,
+                Err(_) => failed.push(i),
+            }
+        }
+
+        failed.sort_unstable();
+        failed.dedup();
+        for i in failed.into_iter().rev() {
+            self.counters.remove(i);
+            self.totals.remove(i);
+        }
+
+        self.runs += 1;
+        result
+    }
+
+    /// Average counts per run since the counters were opened.
+    pub fn stats(&self) -> CounterStats {
+        let totals: Vec<(Event, u64)> = self
+            .counters
+            .iter()
+            .zip(&self.totals)
+            .map(|((event, _), total)| (*event, *total))
+            .collect();
+        CounterStats::from_totals(&totals, self.runs)
+    }
+}
+
+/// Average counts per run of a part, `None` for events that could not be counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CounterStats {
+    pub instructions: Option<f64>,
+    pub cycles: Option<f64>,
+    pub cache_misses: Option<f64>,
+    pub branch_misses: Option<f64>,
+}
+
+impl CounterStats {
+    #[allow(clippy::cast_precision_loss)]
+    fn from_totals(totals: &[(Event, u64)], runs: u64) -> Self {
+        let average = |event: Event| {
+            totals
+                .iter()
+                .find(|(e, _)| *e == event)
+                .filter(|_| runs > 0)
+                .map(|(_, total)| *total as f64 / runs as f64)
+        };
+
+        CounterStats {
+            instructions: average(Event::Instructions),
+            cycles: average(Event::Cycles),
+            cache_misses: average(Event::CacheMisses),
+            branch_misses: average(Event::BranchMisses),
+        }
+    }
+
+    /// Instructions per cycle, if both were counted.
+    pub fn ipc(&self) -> Option<f64> {
+        match (self.instructions, self.cycles) {
+            (Some(instructions), Some(cycles)) if cycles > 0.0 => Some(instructions / cycles),
+            _ => None,
+        }
+    }
+}
+
+/// Formats as printed by the runner, e.g. `1.24M instructions, 930.1k cycles (1.33 IPC), 12 cache misses`.
+impl Display for CounterStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = vec![];
+
+        if let Some(instructions) = self.instructions {
+            parts.push(format!("{} instructions", format_count(instructions)));
+        }
+        if let Some(cycles) = self.cycles {
+            match self.ipc() {
+                Some(ipc) => parts.push(format!("{} cycles ({ipc:.2} IPC)", format_count(cycles))),
+                None => parts.push(format!("{} cycles", format_count(cycles))),
+            }
+        }
+        if let Some(cache_misses) = self.cache_misses {
+            parts.push(format!("{} cache misses", format_count(cache_misses)));
+        }
+        if let Some(branch_misses) = self.branch_misses {
+            parts.push(format!("{} branch misses", format_count(branch_misses)));
+        }
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Formats a count with a metric suffix, e.g. `930.1k` or `1.24M`.
+fn format_count(count: f64) -> String {
+    match count {
+        c if c < 1e3 => format!("{c:.0}"),
+        c if c < 1e6 => format!("{:.1}k", c / 1e3),
+        c if c < 1e9 => format!("{:.2}M", c / 1e6),
+        c => format!("{:.2}G", c / 1e9),
+    }
+}
+
+#[cfg(all(feature = "perf-counters", target_os = "linux"))]
+mod sys {
+    use std::fs::File;
+    use std::io::{self, Read};
+    use std::os::fd::{AsRawFd, FromRawFd};
+
+    /// The leading fields of `struct perf_event_attr`, as of `PERF_ATTR_SIZE_VER0`.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+    // bits of `perf_event_attr.flags`. kernel and hypervisor events are excluded,
+    // so the counters work with the default `perf_event_paranoid` setting.
+    const FLAG_DISABLED: u64 = 1;
+    const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+    const PERF_EVENT_IOC_ENABLE: libc::c_ulong = 0x2400;
+    const PERF_EVENT_IOC_DISABLE: libc::c_ulong = 0x2401;
+    const PERF_EVENT_IOC_RESET: libc::c_ulong = 0x2403;
+
+    /// A counter of a single event for the calling thread.
+    pub struct Counter(File);
+
+    impl Counter {
+        pub fn open(config: u64) -> io::Result<Self> {
+            #[allow(clippy::cast_possible_truncation)]
+            let attr = PerfEventAttr {
+                kind: PERF_TYPE_HARDWARE,
+                size: std::mem::size_of::<PerfEventAttr>() as u32,
+                config,
+                flags: FLAG_DISABLED | FLAG_EXCLUDE_KERNEL | FLAG_EXCLUDE_HV,
+                ..PerfEventAttr::default()
+            };
+
+            // SAFETY: `attr` outlives the call. pid 0 and cpu -1 count the calling thread on any cpu.
+            let fd = unsafe {
+                libc::syscall(
+                    libc::SYS_perf_event_open,
+                    &attr as *const PerfEventAttr,
+                    0 as libc::pid_t,
+                    -1 as libc::c_int,
+                    -1 as libc::c_int,
+                    PERF_FLAG_FD_CLOEXEC,
+                )
+            };
+
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            // SAFETY: the file descriptor was just opened and is owned by nothing else.
+            #[allow(clippy::cast_possible_truncation)]
+            Ok(Counter(unsafe { File::from_raw_fd(fd as libc::c_int) }))
+        }
+
+        fn ioctl(&self, request: libc::c_ulong) -> io::Result<()> {
+            // SAFETY: the perf ioctls used here take no argument.
+            // the request type differs between libc implementations, hence the inferred cast.
+            let result = unsafe { libc::ioctl(self.0.as_raw_fd(), request as _, 0) };
+            if result < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        pub fn start(&self) -> io::Result<()> {
+            self.ioctl(PERF_EVENT_IOC_RESET)?;
+            self.ioctl(PERF_EVENT_IOC_ENABLE)
+        }
+
+        pub fn stop(&mut self) -> io::Result<u64> {
+            self.ioctl(PERF_EVENT_IOC_DISABLE)?;
+            let mut count = [0; 8];
+            self.0.read_exact(&mut count)?;
+            Ok(u64::from_ne_bytes(count))
+        }
+    }
+}
+
+#[cfg(not(all(feature = "perf-counters", target_os = "linux")))]
+mod sys {
+    use std::io;
+
+    pub struct Counter;
+
+    impl Counter {
+        pub fn open(_config: u64) -> io::Result<Self> {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "hardware counters need the `perf-counters` feature on Linux.",
+            ))
+        }
+
+        pub fn start(&self) -> io::Result<()> {
+            Ok(())
+        }
+
+        pub fn stop(&mut self) -> io::Result<u64> {
+            Ok(0)
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_count, CounterStats, Event};
+
+    #[test]
+    fn averages_totals_per_run() {
+        let stats = CounterStats::from_totals(
+            &[(Event::Instructions, 3_000_000), (Event::Cycles, 2_000_000)],
+            2,
+        );
+        assert_eq!(stats.instructions, Some(1_500_000.0));
+        assert_eq!(stats.cycles, Some(1_000_000.0));
+        assert_eq!(stats.cache_misses, None);
+        assert_eq!(stats.ipc(), Some(1.5));
+        assert_eq!(
+            CounterStats::from_totals(&[(Event::Cycles, 1)], 0),
+            CounterStats::default()
+        );
+    }
+
+    #[test]
+    fn formats_available_counters() {
+        let stats = CounterStats {
+            instructions: Some(1_240_000.0),
+            cycles: Some(930_100.0),
+            cache_misses: None,
+            branch_misses: Some(12.0),
+        };
+        assert_eq!(
+            stats.to_string(),
+            "1.24M instructions, 930.1k cycles (1.33 IPC), 12 branch misses"
+        );
+    }
+
+    #[test]
+    fn formats_counts() {
+        assert_eq!(format_count(999.4), "999");
+        assert_eq!(format_count(12_345.0), "12.3k");
+        assert_eq!(format_count(7_500_000_000.0), "7.50G");
+    }
+}
@@ -1,8 +1,9 @@
-/// Module that updates the readme me with timing information.
-/// The approach taken is similar to how `aoc-readme-stars` handles this.
-use std::{fs, io};
+//! Module that updates the readme me with timing information.
+//! The approach taken is similar to how `aoc-readme-stars` handles this.
 
-use crate::template::timings::Timings;
+use std::{fs, io, time::Duration};
+
+use crate::template::timings::{parse_duration, PartTiming, Timing, Timings};
 use crate::template::Day;
 
 static MARKER: &str = "<!--- benchmarking table --->";
@@ -21,8 +22,8 @@ impl From<std::io::Error> for Error {
 }
 
 pub struct TablePosition {
-    pos_start: usize,
-    pos_end: usize,
+    pub pos_start: usize,
+    pub pos_end: usize,
 }
 
 #[must_use]
@@ -30,8 +31,9 @@ pub fn get_path_for_bin(day: Day) -> String {
     format!("./src/bin/{day}.rs")
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+/// Locates the block between two `marker`s. A single marker is replaced by the block.
+pub(crate) fn locate_table(readme: &str, marker: &str) -> Result<TablePosition, Error> {
+    let matches: Vec<_> = readme.match_indices(marker).collect();
 
     if matches.len() > 2 {
         return Err(Error::Parser(
@@ -52,38 +54,323 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
+/// Comment inside the table block that configures the table, e.g.
+/// `<!--- benchmark options: columns=parts,median,share; highlight=1s; chart --->`.
+static OPTIONS_PREFIX: &str = "<!--- benchmark options:";
+static OPTIONS_SUFFIX: &str = "--->";
+
+/// A column (or pair of columns for per-part values) of the benchmark table.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Column {
+    /// Mean time of the `parse` function, for days that declare one.
+    Parse,
+    /// Mean time of each part.
+    Parts,
+    /// Median ± spread of each part.
+    Median,
+    Samples,
+    Allocations,
+    /// Peak memory of each part, counted with `cargo time --allocs`.
+    Memory,
+    /// Total time of the day.
+    Total,
+    /// Share of the total time of all days.
+    Share,
+}
+
+impl Column {
+    fn parse(name: &str) -> Result<Self, Error> {
+        match name.trim() {
+            "parse" => Ok(Column::Parse),
+            "parts" => Ok(Column::Parts),
+            "median" => Ok(Column::Median),
+            "samples" => Ok(Column::Samples),
+            "allocations" => Ok(Column::Allocations),
+            "memory" => Ok(Column::Memory),
+            "total" => Ok(Column::Total),
+            "share" => Ok(Column::Share),
+            name => Err(Error::Parser(format!(
+                "unknown benchmark column `{name}`, expected one of: parse, parts, median, samples, allocations, memory, total, share."
+            ))),
+        }
+    }
+
+    fn headers(self) -> Vec<&'static str> {
+        match self {
+            Column::Parse => vec!["Parse"],
+            Column::Parts => vec!["Part 1", "Part 2"],
+            Column::Median => vec!["Part 1 (median)", "Part 2 (median)"],
+            Column::Samples => vec!["Samples"],
+            Column::Allocations => vec!["Allocations"],
+            Column::Memory => vec!["Peak memory"],
+            Column::Total => vec!["Total"],
+            Column::Share => vec!["Share"],
+        }
+    }
+}
+
+/// How the table is rendered, read from the options comment inside the table block.
+#[derive(Clone, Debug, PartialEq)]
+struct TableOptions {
+    columns: Vec<Column>,
+    /// Durations above this many nanoseconds are highlighted.
+    highlight_nanos: Option<f64>,
+    /// Whether to render a bar chart of each day's share of the total time.
+    chart: bool,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        TableOptions {
+            columns: vec![Column::Parts],
+            highlight_nanos: None,
+            chart: false,
+        }
+    }
+}
+
+impl TableOptions {
+    /// Options used without an options comment: the parse column is shown once any day has a parse time.
+    fn default_for(timings: &Timings) -> Self {
+        let mut options = TableOptions::default();
+        if timings.data.iter().any(|timing| timing.parse.is_some()) {
+            options.columns.insert(0, Column::Parse);
+        }
+        options
+    }
+
+    /// Reads the options comment from the table block, if present.
+    fn from_table(table: &str) -> Result<Option<(String, Self)>, Error> {
+        let Some(line) = table
+            .lines()
+            .find(|line| line.trim_start().starts_with(OPTIONS_PREFIX))
+        else {
+            return Ok(None);
+        };
+
+        let line = line.trim();
+        let options = line
+            .strip_prefix(OPTIONS_PREFIX)
+            .and_then(|options| options.strip_suffix(OPTIONS_SUFFIX))
+            .ok_or_else(|| Error::Parser(format!("malformed benchmark options `{line}`.")))?;
+
+        Ok(Some((line.to_string(), Self::parse(options)?)))
+    }
+
+    fn parse(options: &str) -> Result<Self, Error> {
+        let mut result = TableOptions::default();
+
+        for option in options.split(';').map(str::trim).filter(|o| !o.is_empty()) {
+            match option.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+                Some(("columns", columns)) => {
+                    result.columns = columns
+                        .split(',')
+                        .map(Column::parse)
+                        .collect::<Result<_, _>>()?;
+                }
+                Some(("highlight", duration)) => {
+                    result.highlight_nanos = Some(parse_duration(duration).ok_or_else(|| {
+                        Error::Parser(format!("expected a duration such as `1s`, found `{duration}`."))
+                    })?);
+                }
+                None if option == "chart" => result.chart = true,
+                _ => {
+                    return Err(Error::Parser(format!(
+                        "unknown benchmark option `{option}`, expected `columns=…`, `highlight=…` or `chart`."
+                    )))
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn duration_cell(&self, duration: &str, nanos: f64) -> String {
+        match self.highlight_nanos {
+            Some(threshold) if nanos > threshold => format!("**`{duration}`**"),
+            _ => format!("`{duration}`"),
+        }
+    }
+
+    fn cells(&self, timing: &Timing, total_nanos: f64) -> Vec<String> {
+        let parts = [&timing.part_1, &timing.part_2];
+        let per_part = |f: &dyn Fn(&PartTiming) -> Option<String>| -> String {
+            parts
+                .iter()
+                .map(|part| part.as_ref().and_then(f).unwrap_or_else(|| "-".into()))
+                .collect::<Vec<_>>()
+                .join(" / ")
+        };
+
+        let mut cells = vec![];
+        for column in &self.columns {
+            match column {
+                Column::Parse => cells.push(timing.parse.as_ref().map_or_else(
+                    || "-".into(),
+                    |parse| self.duration_cell(&parse.duration, parse.nanos),
+                )),
+                Column::Parts => {
+                    for part in parts {
+                        cells.push(part.as_ref().map_or_else(
+                            || missing_part(timing),
+                            |part| self.duration_cell(&part.duration, part.nanos),
+                        ));
+                    }
+                }
+                Column::Median => {
+                    for part in parts {
+                        let median = part.as_ref().and_then(|part| {
+                            let (median, spread) = (part.median_nanos?, part.spread_nanos?);
+                            let cell =
+                                format!("{} ± {}", format_nanos(median), format_nanos(spread));
+                            Some(self.duration_cell(&cell, median))
+                        });
+                        cells.push(median.unwrap_or_else(|| "-".into()));
+                    }
+                }
+                Column::Samples => {
+                    cells.push(per_part(&|part| part.samples.map(|s| s.to_string())));
+                }
+                Column::Allocations => {
+                    cells.push(per_part(&|part| part.allocations.map(|a| a.to_string())));
+                }
+                Column::Memory => {
+                    cells.push(per_part(&|part| part.peak_bytes.map(format_bytes)));
+                }
+                Column::Total => {
+                    cells.push(
+                        self.duration_cell(&format_nanos(timing.total_nanos), timing.total_nanos),
+                    );
+                }
+                Column::Share => {
+                    cells.push(format!("{:.1}%", share(timing.total_nanos, total_nanos)))
+                }
+            }
+        }
+        cells
+    }
+}
+
+/// The cell of a part without a timing, marking parts that ran out of time.
+fn missing_part(timing: &Timing) -> String {
+    timing.timed_out_nanos.map_or_else(
+        || "-".into(),
+        |timed_out| format!("⏱ > {}", format_nanos(timed_out)),
+    )
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_nanos(nanos: f64) -> String {
+    format!("{:.1?}", Duration::from_nanos(nanos as u64))
+}
+
+/// Formats bytes with a binary prefix, e.g. `1.5 KiB`.
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    format!("{value:.1} {unit}")
+}
+
+fn share(nanos: f64, total_nanos: f64) -> f64 {
+    if total_nanos > 0.0 {
+        nanos / total_nanos * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// A horizontal bar per day, e.g. `Day 1 │████▌     │ 45.2%`.
+fn construct_chart(timings: &Timings, total_nanos: f64) -> Vec<String> {
+    const WIDTH: usize = 30;
+    const EIGHTHS: [&str; 8] = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+
+    let labels: Vec<String> = timings
+        .data
+        .iter()
+        .map(|timing| format!("Day {}", timing.day.into_inner()))
+        .collect();
+    let label_width = labels.iter().map(String::len).max().unwrap_or(0);
+
+    let mut lines = vec!["```text".to_string()];
+    for (timing, label) in timings.data.iter().zip(labels) {
+        let share = share(timing.total_nanos, total_nanos);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let eighths = (share / 100.0 * (WIDTH * 8) as f64).round() as usize;
+        let bar = format!("{}{}", "█".repeat(eighths / 8), EIGHTHS[eighths % 8]);
+        let padding = WIDTH - bar.chars().count();
+        lines.push(format!(
+            "{label:<label_width$} │{bar}{} │ {share:>5.1}%",
+            " ".repeat(padding)
+        ));
+    }
+    lines.push("```".into());
+    lines
+}
+
+fn construct_table(
+    prefix: &str,
+    timings: Timings,
+    total_millis: f64,
+    options: Option<(String, TableOptions)>,
+) -> String {
     let header = format!("{prefix} Benchmarks");
+    let (options_line, options) = options.unzip();
+    let options = options.unwrap_or_else(|| TableOptions::default_for(&timings));
+    let total_nanos: f64 = timings.data.iter().map(|timing| timing.total_nanos).sum();
+
+    let mut lines: Vec<String> = vec![MARKER.into()];
+    lines.extend(options_line);
+    lines.push(header);
+    lines.push(String::new());
 
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
-    ];
+    let headers: Vec<&str> = options.columns.iter().flat_map(|c| c.headers()).collect();
+    lines.push(format!("| Day | {} |", headers.join(" | ")));
+    lines.push(format!(
+        "| :---: | {}  |",
+        vec![":---:"; headers.len()].join(" | ")
+    ));
 
-    for timing in timings.data {
+    for timing in &timings.data {
         let path = get_path_for_bin(timing.day);
         lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
+            "| [Day {}]({}) | {} |",
             timing.day.into_inner(),
             path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            options.cells(timing, total_nanos).join(" | ")
         ));
     }
 
     lines.push(String::new());
     lines.push(format!("**Total: {total_millis:.2}ms**"));
+
+    if options.chart {
+        lines.push(String::new());
+        lines.extend(construct_chart(&timings, total_nanos));
+    }
+
     lines.push(MARKER.into());
 
     lines.join("\n")
 }
 
 fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+    let positions = locate_table(s, MARKER)?;
+    let options = TableOptions::from_table(&s[positions.pos_start..positions.pos_end])?;
+    let table = construct_table("##", timings, total_millis, options);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
@@ -100,28 +387,40 @@ pub fn update(timings: Timings) -> Result<(), Error> {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use crate::{
+        day,
+        template::{
+            alloc::AllocStats,
+            timings::{PartTiming, Timing, Timings},
+        },
+    };
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
                     day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
+                    parse: None,
+                    part_1: PartTiming::from_duration("10ms", None),
+                    part_2: PartTiming::from_duration("20ms", None),
                     total_nanos: 3e+10,
+                    timed_out_nanos: None,
                 },
                 Timing {
                     day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
+                    parse: None,
+                    part_1: PartTiming::from_duration("30ms", None),
+                    part_2: PartTiming::from_duration("40ms", None),
                     total_nanos: 7e+10,
+                    timed_out_nanos: None,
                 },
                 Timing {
                     day: day!(4),
-                    part_1: Some("40ms".into()),
-                    part_2: Some("50ms".into()),
+                    parse: None,
+                    part_1: PartTiming::from_duration("40ms", None),
+                    part_2: PartTiming::from_duration("50ms", None),
                     total_nanos: 9e+10,
+                    timed_out_nanos: None,
                 },
             ],
         }
@@ -180,4 +479,109 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn formats_configured_columns() {
+        let options = "<!--- benchmark options: columns=median,samples,allocations,share; highlight=25ms; chart --->";
+        let mut s = format!("{MARKER}\n{options}\n{MARKER}");
+        let mut timings = get_mock_timings();
+        timings.data[0].part_1 = timings.data[0]
+            .part_1
+            .take()
+            .map(|part| part.with_median("9ms", "1ms"));
+        timings.data[1].part_2 = timings.data[1]
+            .part_2
+            .take()
+            .map(|part| part.with_median("30ms", "2ms"));
+        timings.data[1].set_allocations(&[("Part 1".into(), 12), ("Part 2".into(), 40)]);
+
+        update_content(&mut s, timings, 190.0).unwrap();
+        let expected = [
+            MARKER,
+            options,
+            "## Benchmarks",
+            "",
+            "| Day | Part 1 (median) | Part 2 (median) | Samples | Allocations | Share |",
+            "| :---: | :---: | :---: | :---: | :---: | :---:  |",
+            "| [Day 1](./src/bin/01.rs) | `9.0ms ± 1.0ms` | - | - / - | - / - | 15.8% |",
+            "| [Day 2](./src/bin/02.rs) | - | **`30.0ms ± 2.0ms`** | - / - | 12 / 40 | 36.8% |",
+            "| [Day 4](./src/bin/04.rs) | - | - | - / - | - / - | 47.4% |",
+            "",
+            "**Total: 190.00ms**",
+            "",
+            "```text",
+            "Day 1 │████▊                          │  15.8%",
+            "Day 2 │███████████                    │  36.8%",
+            "Day 4 │██████████████▎                │  47.4%",
+            "```",
+            MARKER,
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+
+    #[test]
+    fn formats_peak_memory() {
+        let options = "<!--- benchmark options: columns=parts,memory --->";
+        let mut s = format!("{MARKER}\n{options}\n{MARKER}");
+        let mut timings = get_mock_timings();
+        timings.data[0].part_1 = timings.data[0].part_1.take().map(|part| {
+            part.with_allocations(AllocStats {
+                allocations: 2,
+                bytes: 2048,
+                peak_bytes: 1536,
+            })
+        });
+        timings.data[0].part_2 = timings.data[0].part_2.take().map(|part| {
+            part.with_allocations(AllocStats {
+                allocations: 1,
+                bytes: 3_500_000,
+                peak_bytes: 3_500_000,
+            })
+        });
+
+        update_content(&mut s, timings, 190.0).unwrap();
+        assert!(s.contains("| Day | Part 1 | Part 2 | Peak memory |"));
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | 1.5 KiB / 3.3 MiB |"));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` | - / - |"));
+    }
+
+    #[test]
+    fn marks_timed_out_parts() {
+        let mut timings = get_mock_timings();
+        timings.data[2].part_2 = None;
+        timings.data[2].timed_out_nanos = Some(10e9);
+
+        let mut s = format!("{MARKER}{MARKER}");
+        update_content(&mut s, timings, 190.0).unwrap();
+        assert!(s.contains("| [Day 4](./src/bin/04.rs) | `40ms` | ⏱ > 10.0s |"));
+    }
+
+    #[test]
+    fn highlights_slow_parts() {
+        let options = "<!--- benchmark options: columns=parts,total; highlight=35ms --->";
+        let mut s = format!("{MARKER}\n{options}\n{MARKER}");
+        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `30ms` | **`40ms`** | **`70.0s`** |"));
+    }
+
+    #[test]
+    fn errors_for_unknown_options() {
+        for options in ["columns=parts,speed", "highlight=soon", "sparkles"] {
+            let mut s = format!("{MARKER}\n<!--- benchmark options: {options} --->\n{MARKER}");
+            assert!(update_content(&mut s, get_mock_timings(), 190.0).is_err());
+        }
+    }
+
+    #[test]
+    fn adds_parse_column_if_parse_times_are_known() {
+        let mut timings = get_mock_timings();
+        timings.data[1].parse = PartTiming::from_duration("5ms", None);
+
+        let mut s = format!("{MARKER}{MARKER}");
+        update_content(&mut s, timings, 190.0).unwrap();
+        assert!(s.contains("| Day | Parse | Part 1 | Part 2 |"));
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | - | `10ms` | `20ms` |"));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `5ms` | `30ms` | `40ms` |"));
+    }
 }
@@ -0,0 +1,196 @@
+//! Module that updates the readme with the collected stars.
+//! Stars are taken from the local answer ledger and from the stored timings of solved parts,
+//! so no requests to adventofcode.com are needed.
+
+use std::fs;
+
+use crate::template::readme_benchmarks::{locate_table, Error};
+use crate::template::timings::Timings;
+use crate::template::{all_days, aoc_cli, ledger, Day, ANSI_BOLD, ANSI_RESET};
+
+static MARKER: &str = "<!--- advent_readme_stars table --->";
+
+/// The stars of a single day.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DayStars {
+    pub part_1: bool,
+    pub part_2: bool,
+}
+
+impl DayStars {
+    fn count(self) -> usize {
+        usize::from(self.part_1) + usize::from(self.part_2)
+    }
+}
+
+/// The stars of every day of advent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stars {
+    pub days: Vec<(Day, DayStars)>,
+}
+
+impl Stars {
+    /// A part earns a star if its answer was recorded in the ledger, or if it was solved and timed.
+    pub fn collect(timings: &Timings) -> Result<Self, String> {
+        let days = all_days()
+            .map(|day| {
+                let answers = ledger::read(day)?;
+                let timing = timings.data.iter().find(|timing| timing.day == day);
+                let stars = DayStars {
+                    part_1: answers.part_one.is_some()
+                        || timing.is_some_and(|timing| timing.part_1.is_some()),
+                    part_2: answers.part_two.is_some()
+                        || timing.is_some_and(|timing| timing.part_2.is_some()),
+                };
+                Ok((day, stars))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Stars { days })
+    }
+
+    pub fn total(&self) -> usize {
+        self.days.iter().map(|(_, stars)| stars.count()).sum()
+    }
+
+    /// The days of advent in rows of five, e.g. ` 1 ★★   2 ★☆   3 ☆☆`.
+    pub fn calendar(&self, year: Option<u16>) -> String {
+        let title = year.map_or_else(
+            || "Advent of Code".into(),
+            |year| format!("Advent of Code {year}"),
+        );
+        let mut lines = vec![
+            format!("{ANSI_BOLD}{title}: {} / 50 ★{ANSI_RESET}", self.total()),
+            String::new(),
+        ];
+
+        for row in self.days.chunks(5) {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|(day, stars)| {
+                    let star = |earned: bool| if earned { '★' } else { '☆' };
+                    format!(
+                        "{:>2} {}{}",
+                        day.into_inner(),
+                        star(stars.part_1),
+                        star(stars.part_2)
+                    )
+                })
+                .collect();
+            lines.push(cells.join("   "));
+        }
+
+        lines.join("\n")
+    }
+}
+
+fn construct_table(stars: &Stars, year: Option<u16>) -> String {
+    let header = year.map_or_else(|| "## Results".into(), |year| format!("## {year} Results"));
+    let year_path = year.map_or_else(String::new, |year| format!("/{year}"));
+
+    let mut lines: Vec<String> = vec![
+        MARKER.into(),
+        header,
+        String::new(),
+        "| Day | Part 1 | Part 2 |".into(),
+        "| :---: | :---: | :---: |".into(),
+    ];
+
+    for (day, stars) in &stars.days {
+        if stars.count() == 0 {
+            continue;
+        }
+        let star = |earned: bool| if earned { "⭐" } else { " " };
+        lines.push(format!(
+            "| [Day {0}](https://adventofcode.com{year_path}/day/{0}) | {1} | {2} |",
+            day.into_inner(),
+            star(stars.part_1),
+            star(stars.part_2)
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(format!("**Stars: {} / 50**", stars.total()));
+    lines.push(MARKER.into());
+
+    lines.join("\n")
+}
+
+fn update_content(s: &mut String, stars: &Stars, year: Option<u16>) -> Result<(), Error> {
+    let positions = locate_table(s, MARKER)?;
+    let table = construct_table(stars, year);
+    s.replace_range(positions.pos_start..positions.pos_end, &table);
+    Ok(())
+}
+
+pub fn update(stars: &Stars) -> Result<(), Error> {
+    let path = "README.md";
+    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
+    update_content(&mut readme, stars, aoc_cli::get_year())?;
+    fs::write(path, &readme)?;
+    Ok(())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{update_content, DayStars, Stars, MARKER};
+    use crate::template::all_days;
+
+    fn get_mock_stars() -> Stars {
+        Stars {
+            days: all_days()
+                .map(|day| {
+                    let stars = DayStars {
+                        part_1: day.into_inner() <= 3,
+                        part_2: day.into_inner() <= 2,
+                    };
+                    (day, stars)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn fills_single_marker() {
+        let mut s = format!("# readme\n\n{MARKER}\n\nfoo");
+        update_content(&mut s, &get_mock_stars(), Some(2024)).unwrap();
+        let expected = [
+            "# readme",
+            "",
+            MARKER,
+            "## 2024 Results",
+            "",
+            "| Day | Part 1 | Part 2 |",
+            "| :---: | :---: | :---: |",
+            "| [Day 1](https://adventofcode.com/2024/day/1) | ⭐ | ⭐ |",
+            "| [Day 2](https://adventofcode.com/2024/day/2) | ⭐ | ⭐ |",
+            "| [Day 3](https://adventofcode.com/2024/day/3) | ⭐ |   |",
+            "",
+            "**Stars: 5 / 50**",
+            MARKER,
+            "",
+            "foo",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+
+    #[test]
+    fn updates_existing_table() {
+        let mut s = format!("{MARKER}{MARKER}");
+        update_content(&mut s, &get_mock_stars(), None).unwrap();
+        update_content(&mut s, &get_mock_stars(), None).unwrap();
+        assert_eq!(s.matches(MARKER).count(), 2);
+        assert_eq!(s.matches("## Results").count(), 1);
+        assert!(s.contains("| [Day 1](https://adventofcode.com/day/1) | ⭐ | ⭐ |"));
+    }
+
+    #[test]
+    fn draws_calendar() {
+        let calendar = get_mock_stars().calendar(Some(2024));
+        let lines: Vec<&str> = calendar.lines().collect();
+        assert!(lines[0].contains("Advent of Code 2024: 5 / 50 ★"));
+        assert_eq!(lines[2], " 1 ★★    2 ★★    3 ★☆    4 ☆☆    5 ☆☆");
+        assert_eq!(lines[6], "21 ☆☆   22 ☆☆   23 ☆☆   24 ☆☆   25 ☆☆");
+    }
+}
@@ -0,0 +1,636 @@
+//! Machine-readable reports of `cargo all` and `cargo time`, selected with `--format`.
+//! With a format other than text, the runs print nothing else to stdout, so the report can be piped into a file.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+use tinyjson::JsonValue;
+
+use crate::template::runner::format_speedup;
+use crate::template::Day;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// The regular terminal output.
+    #[default]
+    Text,
+    Json,
+    /// JUnit XML, with a test suite per day and a test case per part.
+    Junit,
+    Markdown,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(ReportFormat::Text),
+            "json" => Ok(ReportFormat::Json),
+            "junit" => Ok(ReportFormat::Junit),
+            "markdown" => Ok(ReportFormat::Markdown),
+            _ => Err(format!(
+                "expected one of `text`, `json`, `junit` or `markdown`, found `{s}`."
+            )),
+        }
+    }
+}
+
+/// How the run of a day ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    /// The day did not compile, or its bin exited with an error.
+    Failed,
+    TimedOut,
+    /// The budget was used up before the day could start.
+    Skipped,
+    /// The day has not been scaffolded, or its bin did not print anything.
+    NotSolved,
+}
+
+impl Outcome {
+    fn name(self) -> &'static str {
+        match self {
+            Outcome::Passed => "passed",
+            Outcome::Failed => "failed",
+            Outcome::TimedOut => "timed out",
+            Outcome::Skipped => "skipped",
+            Outcome::NotSolved => "not solved",
+        }
+    }
+}
+
+/// How a single part of a day ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartStatus {
+    Solved,
+    /// The part returned `None`.
+    Unsolved,
+    TimedOut,
+    /// The day stopped before the part ran.
+    NotRun,
+}
+
+impl PartStatus {
+    fn name(self) -> &'static str {
+        match self {
+            PartStatus::Solved => "solved",
+            PartStatus::Unsolved => "unsolved",
+            PartStatus::TimedOut => "timed out",
+            PartStatus::NotRun => "not run",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartReport {
+    /// `Parse`, `Part 1` or `Part 2`.
+    pub label: String,
+    pub status: PartStatus,
+    /// The answer as printed, `None` for the parse step.
+    pub answer: Option<String>,
+    /// The duration as printed by the runner, the mean if the part was benched.
+    pub duration: Option<String>,
+    pub nanos: Option<f64>,
+    /// Alternative implementations benched against the part, the part itself first.
+    pub variants: Vec<VariantReport>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariantReport {
+    pub name: String,
+    pub answer: Option<String>,
+    /// The mean duration as printed by the runner.
+    pub duration: Option<String>,
+    pub nanos: Option<f64>,
+    /// How many times faster than the baseline, `None` for the baseline itself.
+    pub speedup: Option<f64>,
+    /// Whether the answer matches the one of the baseline.
+    pub agrees: bool,
+}
+
+impl VariantReport {
+    fn comparison(&self, baseline: &str) -> String {
+        if self.agrees {
+            self.speedup.map_or(String::new(), format_speedup)
+        } else {
+            format!("disagrees with {baseline}")
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DayReport {
+    pub day: Day,
+    pub outcome: Outcome,
+    /// Wall-clock time of the day, including compiling it.
+    pub elapsed: Duration,
+    pub parts: Vec<PartReport>,
+    /// What the day printed to stderr, e.g. compile errors or a panic message.
+    /// Only kept if the output of the day was held back.
+    pub errors: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    pub days: Vec<DayReport>,
+    /// Wall-clock time of the whole run.
+    pub elapsed: Duration,
+}
+
+impl Report {
+    /// Number of days that failed or timed out.
+    pub fn failures(&self) -> usize {
+        self.days
+            .iter()
+            .filter(|day| matches!(day.outcome, Outcome::Failed | Outcome::TimedOut))
+            .count()
+    }
+
+    /// Renders the report, `None` for [`ReportFormat::Text`] which is printed while the days run.
+    pub fn render(&self, format: ReportFormat) -> Option<String> {
+        match format {
+            ReportFormat::Text => None,
+            ReportFormat::Json => Some(self.to_json()),
+            ReportFormat::Junit => Some(self.to_junit()),
+            ReportFormat::Markdown => Some(self.to_markdown()),
+        }
+    }
+
+    /// Days that were scaffolded, the others are left out of the reports.
+    fn scaffolded_days(&self) -> impl Iterator<Item = &DayReport> {
+        self.days
+            .iter()
+            .filter(|day| day.outcome != Outcome::NotSolved || !day.errors.is_empty())
+    }
+
+    fn to_json(&self) -> String {
+        let days = self
+            .scaffolded_days()
+            .map(|day| {
+                let mut map: HashMap<String, JsonValue> = HashMap::new();
+                map.insert("day".into(), JsonValue::String(day.day.to_string()));
+                map.insert("outcome".into(), day.outcome.name().to_string().into());
+                map.insert("seconds".into(), day.elapsed.as_secs_f64().into());
+                map.insert(
+                    "parts".into(),
+                    JsonValue::Array(day.parts.iter().map(JsonValue::from).collect()),
+                );
+                map.insert(
+                    "errors".into(),
+                    JsonValue::Array(day.errors.iter().cloned().map(JsonValue::from).collect()),
+                );
+                JsonValue::Object(map)
+            })
+            .collect();
+
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        map.insert("seconds".into(), self.elapsed.as_secs_f64().into());
+        map.insert("days".into(), JsonValue::Array(days));
+
+        JsonValue::Object(map)
+            .format()
+            .expect("reports are always valid JSON.")
+    }
+
+    fn to_junit(&self) -> String {
+        let mut suites = String::new();
+        // failed, errored and skipped test cases.
+        let mut totals = [0; 3];
+        let mut total_tests = 0;
+
+        for day in self.scaffolded_days() {
+            let mut cases = String::new();
+            let mut counts = [0; 3];
+            let mut tests = 0;
+
+            for part in &day.parts {
+                tests += 1;
+                #[allow(clippy::cast_precision_loss)]
+                let seconds = part.nanos.map_or(0.0, |nanos| nanos / 1e9);
+                let _ = write!(
+                    cases,
+                    "    <testcase name=\"{}\" classname=\"day{}\" time=\"{seconds:.6}\"",
+                    part.label, day.day
+                );
+
+                let result = match part.status {
+                    PartStatus::Solved => None,
+                    PartStatus::Unsolved => {
+                        counts[2] += 1;
+                        Some("<skipped message=\"no answer\"/>".to_string())
+                    }
+                    PartStatus::TimedOut => {
+                        counts[0] += 1;
+                        Some("<failure message=\"timed out\"/>".to_string())
+                    }
+                    PartStatus::NotRun if day.outcome == Outcome::Skipped => {
+                        counts[2] += 1;
+                        Some("<skipped message=\"the budget is used up\"/>".to_string())
+                    }
+                    PartStatus::NotRun => {
+                        counts[1] += 1;
+                        Some(format!(
+                            "<error message=\"day {} {}\"/>",
+                            day.day,
+                            day.outcome.name()
+                        ))
+                    }
+                };
+
+                match (result, &part.answer) {
+                    (Some(result), _) => {
+                        let _ = writeln!(cases, ">\n      {result}\n    </testcase>");
+                    }
+                    (None, Some(answer)) => {
+                        let _ = writeln!(
+                            cases,
+                            ">\n      <system-out>{}</system-out>\n    </testcase>",
+                            escape_xml(answer)
+                        );
+                    }
+                    (None, None) => {
+                        let _ = writeln!(cases, "/>");
+                    }
+                }
+
+                let Some((baseline, variants)) = part.variants.split_first() else {
+                    continue;
+                };
+                for variant in variants {
+                    tests += 1;
+                    let _ = write!(
+                        cases,
+                        "    <testcase name=\"{} ({})\" classname=\"day{}\" time=\"{:.6}\">\n      ",
+                        part.label,
+                        escape_xml(&variant.name),
+                        day.day,
+                        variant.nanos.map_or(0.0, |nanos| nanos / 1e9)
+                    );
+                    if variant.agrees {
+                        let _ = write!(
+                            cases,
+                            "<system-out>{}</system-out>",
+                            escape_xml(&variant.comparison(&baseline.name))
+                        );
+                    } else {
+                        counts[0] += 1;
+                        let _ = write!(
+                            cases,
+                            "<failure message=\"{}\"/>",
+                            escape_xml(&variant.comparison(&baseline.name))
+                        );
+                    }
+                    let _ = writeln!(cases, "\n    </testcase>");
+                }
+            }
+
+            if !day.errors.is_empty() {
+                let _ = writeln!(
+                    cases,
+                    "    <system-err>{}</system-err>",
+                    escape_xml(&day.errors.join("\n"))
+                );
+            }
+
+            let _ = write!(
+                suites,
+                "  <testsuite name=\"Day {}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n{cases}  </testsuite>\n",
+                day.day,
+                tests,
+                counts[0],
+                counts[1],
+                counts[2],
+                day.elapsed.as_secs_f64()
+            );
+
+            for (total, count) in totals.iter_mut().zip(counts) {
+                *total += count;
+            }
+            total_tests += tests;
+        }
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"advent_of_code\" tests=\"{total_tests}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n{suites}</testsuites>\n",
+            totals[0],
+            totals[1],
+            totals[2],
+            self.elapsed.as_secs_f64()
+        )
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut markdown = String::from(
+            "| Day | Part | Answer | Status | Time |\n| :---: | :---: | --- | --- | ---: |\n",
+        );
+
+        for day in self.scaffolded_days() {
+            if day.parts.is_empty() {
+                let _ = writeln!(
+                    markdown,
+                    "| [Day {}](./src/bin/{}.rs) | | | {} | {:.1?} |",
+                    day.day.into_inner(),
+                    day.day,
+                    day.outcome.name(),
+                    day.elapsed
+                );
+            }
+
+            for part in &day.parts {
+                let answer = part.answer.as_ref().map_or(String::new(), |answer| {
+                    // multi-line answers, e.g. letters drawn on a grid, don't fit in a table cell.
+                    format!("`{}`", answer.replace('|', "\\|").replace('\n', "` `"))
+                });
+                let _ = writeln!(
+                    markdown,
+                    "| [Day {}](./src/bin/{}.rs) | {} | {answer} | {} | {} |",
+                    day.day.into_inner(),
+                    day.day,
+                    part.label,
+                    part.status.name(),
+                    part.duration.as_deref().unwrap_or("-"),
+                );
+
+                let Some(baseline) = part.variants.first() else {
+                    continue;
+                };
+                // the baseline is benched again next to its variants, so it is listed as well.
+                for (i, variant) in part.variants.iter().enumerate() {
+                    let status = if i == 0 {
+                        "baseline".to_string()
+                    } else {
+                        variant.comparison(&baseline.name)
+                    };
+                    let answer = variant.answer.as_ref().map_or(String::new(), |answer| {
+                        format!("`{}`", answer.replace('|', "\\|"))
+                    });
+                    let _ = writeln!(
+                        markdown,
+                        "| [Day {}](./src/bin/{}.rs) | {} · {} | {answer} | {} | {} |",
+                        day.day.into_inner(),
+                        day.day,
+                        part.label,
+                        variant.name,
+                        status,
+                        variant.duration.as_deref().unwrap_or("-"),
+                    );
+                }
+            }
+        }
+
+        let outcomes: Vec<Outcome> = self.days.iter().map(|day| day.outcome).collect();
+        let _ = writeln!(
+            markdown,
+            "\n**{}** in {:.1?}",
+            summarize(&outcomes),
+            self.elapsed
+        );
+
+        for day in self.scaffolded_days().filter(|day| !day.errors.is_empty()) {
+            let _ = writeln!(
+                markdown,
+                "\n#### Day {}\n\n```\n{}\n```",
+                day.day,
+                day.errors.join("\n")
+            );
+        }
+
+        markdown
+    }
+}
+
+impl From<&PartReport> for JsonValue {
+    fn from(value: &PartReport) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("label".into(), value.label.clone().into());
+        map.insert("status".into(), value.status.name().to_string().into());
+        let optional = [
+            ("answer", value.answer.clone().map(JsonValue::from)),
+            ("duration", value.duration.clone().map(JsonValue::from)),
+            ("nanos", value.nanos.map(JsonValue::from)),
+        ];
+        for (key, value) in optional {
+            map.insert(key.into(), value.unwrap_or(JsonValue::Null));
+        }
+        map.insert(
+            "variants".into(),
+            JsonValue::Array(value.variants.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl From<&VariantReport> for JsonValue {
+    fn from(value: &VariantReport) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("name".into(), value.name.clone().into());
+        map.insert("agrees".into(), value.agrees.into());
+        let optional = [
+            ("answer", value.answer.clone().map(JsonValue::from)),
+            ("duration", value.duration.clone().map(JsonValue::from)),
+            ("nanos", value.nanos.map(JsonValue::from)),
+            ("speedup", value.speedup.map(JsonValue::from)),
+        ];
+        for (key, value) in optional {
+            map.insert(key.into(), value.unwrap_or(JsonValue::Null));
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+/// Counts the outcomes, e.g. `3 passed, 1 failed, 1 timed out`.
+pub fn summarize(outcomes: &[Outcome]) -> String {
+    let count = |outcome: Outcome| outcomes.iter().filter(|o| **o == outcome).count();
+
+    let mut parts = vec![
+        format!("{} passed", count(Outcome::Passed)),
+        format!("{} failed", count(Outcome::Failed)),
+    ];
+    for outcome in [Outcome::TimedOut, Outcome::Skipped, Outcome::NotSolved] {
+        if count(outcome) > 0 {
+            parts.push(format!("{} {}", count(outcome), outcome.name()));
+        }
+    }
+    parts.join(", ")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        summarize, DayReport, Outcome, PartReport, PartStatus, Report, ReportFormat, VariantReport,
+    };
+    use crate::day;
+    use std::time::Duration;
+    use tinyjson::JsonValue;
+
+    fn part(label: &str, status: PartStatus, answer: Option<&str>) -> PartReport {
+        PartReport {
+            label: label.into(),
+            status,
+            answer: answer.map(String::from),
+            duration: Some("1.5ms".into()),
+            nanos: Some(1_500_000.0),
+            variants: vec![],
+        }
+    }
+
+    fn variant(name: &str, answer: &str, speedup: Option<f64>, agrees: bool) -> VariantReport {
+        VariantReport {
+            name: name.into(),
+            answer: Some(answer.into()),
+            duration: Some("1.5ms".into()),
+            nanos: Some(1_500_000.0),
+            speedup,
+            agrees,
+        }
+    }
+
+    fn report() -> Report {
+        Report {
+            days: vec![
+                DayReport {
+                    day: day!(1),
+                    outcome: Outcome::Passed,
+                    elapsed: Duration::from_millis(400),
+                    parts: vec![
+                        part("Part 1", PartStatus::Solved, Some("42")),
+                        part("Part 2", PartStatus::Unsolved, None),
+                    ],
+                    errors: vec![],
+                },
+                DayReport {
+                    day: day!(2),
+                    outcome: Outcome::Failed,
+                    elapsed: Duration::from_millis(300),
+                    parts: vec![
+                        part("Part 1", PartStatus::Solved, Some("a<b")),
+                        part("Part 2", PartStatus::NotRun, None),
+                    ],
+                    errors: vec!["thread 'main' panicked at src/bin/02.rs:3:5".into()],
+                },
+                DayReport {
+                    day: day!(3),
+                    outcome: Outcome::NotSolved,
+                    elapsed: Duration::ZERO,
+                    parts: vec![],
+                    errors: vec![],
+                },
+            ],
+            elapsed: Duration::from_millis(750),
+        }
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!("junit".parse(), Ok(ReportFormat::Junit));
+        assert!("xml".parse::<ReportFormat>().is_err());
+        assert_eq!(report().render(ReportFormat::Text), None);
+    }
+
+    #[test]
+    fn renders_json() {
+        let json: JsonValue = report()
+            .render(ReportFormat::Json)
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        let days = json["days"].get::<Vec<JsonValue>>().unwrap();
+        assert_eq!(days.len(), 2, "days that were not scaffolded are left out.");
+        assert_eq!(days[0]["day"], JsonValue::from("01".to_string()));
+        assert_eq!(
+            days[0]["parts"][0]["answer"],
+            JsonValue::from("42".to_string())
+        );
+        assert_eq!(days[0]["parts"][1]["answer"], JsonValue::Null);
+        assert_eq!(days[1]["outcome"], JsonValue::from("failed".to_string()));
+        assert_eq!(
+            days[1]["parts"][1]["status"],
+            JsonValue::from("not run".to_string())
+        );
+    }
+
+    #[test]
+    fn renders_junit() {
+        let junit = report().render(ReportFormat::Junit).unwrap();
+        assert!(junit.contains(
+            "<testsuites name=\"advent_of_code\" tests=\"4\" failures=\"0\" errors=\"1\" skipped=\"1\" time=\"0.750\">"
+        ));
+        assert!(junit.contains(
+            "<testcase name=\"Part 1\" classname=\"day01\" time=\"0.001500\">\n      <system-out>42</system-out>"
+        ));
+        assert!(junit.contains("<system-out>a&lt;b</system-out>"));
+        assert!(junit.contains("<error message=\"day 02 failed\"/>"));
+        assert!(!junit.contains("Day 03"));
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let markdown = report().render(ReportFormat::Markdown).unwrap();
+        assert!(markdown.contains("| [Day 1](./src/bin/01.rs) | Part 1 | `42` | solved | 1.5ms |"));
+        assert!(markdown.contains("**1 passed, 1 failed, 1 not solved** in 750.0ms"));
+        assert!(markdown.contains("#### Day 02\n\n```\nthread 'main' panicked"));
+    }
+
+    #[test]
+    fn renders_variants() {
+        let mut report = report();
+        report.days[0].parts[0].variants = vec![
+            variant("part_one", "42", None, true),
+            variant("part_one_fast", "42", Some(2.5), true),
+            variant("part_one_wrong", "41", Some(0.5), false),
+        ];
+
+        let junit = report.render(ReportFormat::Junit).unwrap();
+        assert!(junit.contains("<testsuite name=\"Day 01\" tests=\"4\" failures=\"1\""));
+        assert!(junit.contains("<testcase name=\"Part 1 (part_one_fast)\" classname=\"day01\" time=\"0.001500\">\n      <system-out>2.50× faster</system-out>"));
+        assert!(junit.contains("<failure message=\"disagrees with part_one\"/>"));
+
+        let markdown = report.render(ReportFormat::Markdown).unwrap();
+        assert!(markdown.contains("| Part 1 · part_one | `42` | baseline | 1.5ms |"));
+        assert!(markdown.contains("| Part 1 · part_one_fast | `42` | 2.50× faster | 1.5ms |"));
+        assert!(markdown
+            .contains("| Part 1 · part_one_wrong | `41` | disagrees with part_one | 1.5ms |"));
+
+        let json: JsonValue = report.render(ReportFormat::Json).unwrap().parse().unwrap();
+        let variants = &json["days"][0]["parts"][0]["variants"];
+        assert_eq!(variants[0]["speedup"], JsonValue::Null);
+        assert_eq!(variants[1]["speedup"], JsonValue::from(2.5));
+        assert_eq!(variants[2]["agrees"], JsonValue::from(false));
+    }
+
+    #[test]
+    fn counts_failures() {
+        let mut report = report();
+        assert_eq!(report.failures(), 1);
+
+        report.days[0].outcome = Outcome::TimedOut;
+        report.days[1].outcome = Outcome::Skipped;
+        assert_eq!(report.failures(), 1);
+    }
+
+    #[test]
+    fn summarizes_outcomes() {
+        assert_eq!(
+            summarize(&[Outcome::Passed, Outcome::Passed, Outcome::NotSolved]),
+            "2 passed, 0 failed, 1 not solved"
+        );
+        assert_eq!(
+            summarize(&[Outcome::Failed, Outcome::TimedOut, Outcome::Skipped]),
+            "0 passed, 1 failed, 1 timed out, 1 skipped"
+        );
+    }
+}
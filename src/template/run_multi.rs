@@ -1,49 +1,382 @@
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
 use std::{collections::HashSet, io};
 
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::template::alloc::AllocationCounting;
+use crate::template::report::{
+    summarize, DayReport, Outcome, PartReport, PartStatus, Report, VariantReport,
+};
+use crate::template::{parse_duration, Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
 use super::{
     all_days,
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
-    let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+/// How solutions are run: what is measured on top of their run time, and how long they may take.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RunOptions {
+    pub allocations: AllocationCounting,
+    /// Read hardware counters while benching, see [`perf`](crate::template::perf).
+    pub counters: bool,
+    /// Time a single day may run before it is stopped. Compiling the day does not count.
+    pub timeout: Option<Duration>,
+    /// Time all days together may take, including compiling them.
+    /// Days still running when it is used up are stopped, the remaining days are skipped.
+    pub budget: Option<Duration>,
+    /// Days run at once by untimed runs, the available parallelism if unset.
+    /// Timed runs always run one day at a time, so the days do not disturb each other's benchmarks.
+    pub jobs: Option<NonZeroUsize>,
+}
 
-    let mut need_space = false;
+impl RunOptions {
+    /// The cargo features the solution bins are built with.
+    fn features(self) -> Vec<&'static str> {
+        let mut features = vec![];
+        if self.allocations == AllocationCounting::Counter {
+            features.push("count-allocations");
+        }
+        if self.counters {
+            features.push("perf-counters");
+        }
+        features
+    }
 
-    // NOTE: use non-duplicate, sorted day values.
-    all_days()
-        .filter(|day| days_to_run.contains(day))
-        .for_each(|day| {
-            if need_space {
-                println!();
-            }
-            need_space = true;
+    fn jobs(self, is_timed: bool) -> usize {
+        if is_timed {
+            return 1;
+        }
+        self.jobs
+            .or_else(|| thread::available_parallelism().ok())
+            .map_or(1, NonZeroUsize::get)
+    }
+}
+
+/// A day that was run, or skipped.
+struct DayRun {
+    day: Day,
+    /// `None` if the day was skipped because the budget was used up.
+    output: Option<child_commands::Output>,
+    elapsed: Duration,
+}
+
+impl DayRun {
+    fn outcome(&self) -> Outcome {
+        match &self.output {
+            None => Outcome::Skipped,
+            Some(output) if output.timed_out.is_some() => Outcome::TimedOut,
+            Some(output) if !output.success => Outcome::Failed,
+            Some(output) if output.lines.is_empty() => Outcome::NotSolved,
+            Some(_) => Outcome::Passed,
+        }
+    }
+
+    /// The answer, status and duration of each part the day printed.
+    /// Days that did not pass also list the parts they did not get to.
+    fn report(&self) -> DayReport {
+        let outcome = self.outcome();
+        let (lines, errors) = self.output.as_ref().map_or((&[][..], vec![]), |output| {
+            (&output.lines[..], output.stderr.clone())
+        });
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
+        let printed = child_commands::parse_parts(lines);
+        let variants = child_commands::parse_variants(lines);
+        let unfinished = match outcome {
+            Outcome::Passed | Outcome::NotSolved => vec![],
+            _ => child_commands::unfinished_parts(lines),
+        };
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+        let parts = ["Parse", "Part 1", "Part 2"]
+            .into_iter()
+            .filter_map(|label| {
+                if let Some(part) = printed.iter().find(|part| part.label == label) {
+                    return Some(PartReport {
+                        label: label.into(),
+                        status: if part.solved {
+                            PartStatus::Solved
+                        } else {
+                            PartStatus::Unsolved
+                        },
+                        answer: part.answer.clone(),
+                        nanos: part.duration.as_deref().and_then(parse_duration),
+                        duration: part.duration.clone(),
+                        variants: variant_reports(&variants, label),
+                    });
+                }
+                unfinished.contains(&label).then(|| PartReport {
+                    label: label.into(),
+                    status: if outcome == Outcome::TimedOut {
+                        PartStatus::TimedOut
+                    } else {
+                        PartStatus::NotRun
+                    },
+                    answer: None,
+                    duration: None,
+                    nanos: None,
+                    variants: vec![],
+                })
+            })
+            .collect();
 
-            if output.is_empty() {
-                println!("Not solved.");
-            } else {
-                let val = child_commands::parse_exec_time(&output, day);
-                timings.push(val);
+        DayReport {
+            day: self.day,
+            outcome,
+            elapsed: self.elapsed,
+            parts,
+            errors: errors
+                .iter()
+                .map(|line| child_commands::strip_ansi(line))
+                .collect(),
+        }
+    }
+}
+
+/// The variants of a part, compared to the first one, which is the part itself.
+fn variant_reports(variants: &[child_commands::PrintedVariant], part: &str) -> Vec<VariantReport> {
+    let variants: Vec<&child_commands::PrintedVariant> = variants
+        .iter()
+        .filter(|variant| variant.part == part)
+        .collect();
+    let Some(baseline) = variants.first() else {
+        return vec![];
+    };
+    let baseline_nanos = baseline.duration.as_deref().and_then(parse_duration);
+
+    variants
+        .iter()
+        .enumerate()
+        .map(|(i, variant)| {
+            let nanos = variant.duration.as_deref().and_then(parse_duration);
+            VariantReport {
+                name: variant.name.clone(),
+                answer: variant.answer.clone(),
+                duration: variant.duration.clone(),
+                nanos,
+                speedup: baseline_nanos
+                    .zip(nanos)
+                    .filter(|_| i > 0)
+                    .map(|(baseline, nanos)| baseline / nanos),
+                agrees: variant.agrees,
             }
-        });
+        })
+        .collect()
+}
+
+/// With [`AllocationCounting::Counter`], solutions are built with the counting allocator and print their allocations.
+/// With [`AllocationCounting::Dhat`], timed solutions are run once more with the dhat heap profiler.
+/// Days that run out of time are reported as timed out, see [`RunOptions::timeout`].
+///
+/// Untimed runs run several days at once, see [`RunOptions::jobs`]. Their output is held back
+/// and printed in day order, followed by a summary of which days passed.
+/// With `quiet`, nothing is printed to stdout, so the caller can print the returned [`Report`] there.
+/// Only what the days print to stderr is passed on.
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+    options: RunOptions,
+    quiet: bool,
+) -> (Report, Option<Timings>) {
+    let started = Instant::now();
+    let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+    let mut report = Report::default();
+    let budget_end = options.budget.map(|budget| Instant::now() + budget);
+    let jobs = options.jobs(is_timed);
+
+    // NOTE: use non-duplicate, sorted day values.
+    let days: Vec<Day> = all_days().filter(|day| days_to_run.contains(day)).collect();
+
+    let run_day = |day: Day, echo: bool| {
+        let started = Instant::now();
+        let output = if budget_end.is_some_and(|end| started >= end) {
+            None
+        } else {
+            Some(
+                child_commands::run_solution(day, is_timed, is_release, options, budget_end, echo)
+                    .unwrap(),
+            )
+        };
+        DayRun {
+            day,
+            output,
+            elapsed: started.elapsed(),
+        }
+    };
+
+    let mut finish_day = |run: DayRun| {
+        if !quiet {
+            print_day(&run);
+        }
+        report.days.push(run.report());
+        if let Some(timing) = day_timing(&run, is_timed, options) {
+            timings.push(timing);
+        }
+    };
+
+    if jobs == 1 && !quiet {
+        for (i, day) in days.iter().enumerate() {
+            print_day_header(i, *day);
+            finish_day(run_day(*day, true));
+        }
+    } else {
+        run_parallel(
+            &days,
+            jobs,
+            |day| run_day(day, false),
+            |i, run| {
+                match &run.output {
+                    Some(output) if quiet => {
+                        output.stderr.iter().for_each(|line| eprintln!("{line}"))
+                    }
+                    Some(output) => {
+                        print_day_header(i, run.day);
+                        output.print();
+                    }
+                    None if !quiet => print_day_header(i, run.day),
+                    None => {}
+                }
+                finish_day(run);
+            },
+        );
+    }
+
+    report.elapsed = started.elapsed();
 
     if is_timed {
         let timings = Timings { data: timings };
-        let total_millis = timings.total_millis();
+        if !quiet {
+            let total_millis = timings.total_millis();
+            println!(
+                "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
+            );
+        }
+        (report, Some(timings))
+    } else {
+        if !quiet {
+            print_summary(&report, jobs);
+        }
+        (report, None)
+    }
+}
+
+fn print_day_header(index: usize, day: Day) {
+    if index > 0 {
+        println!();
+    }
+    println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+    println!("------");
+}
+
+/// Prints how the run of a day ended, after its output.
+fn print_day(run: &DayRun) {
+    let Some(output) = &run.output else {
+        println!("⏱ Skipped, the budget is used up.");
+        return;
+    };
+
+    if let Some(limit) = output.timed_out {
+        for part in child_commands::unfinished_parts(&output.lines) {
+            println!("{part}: ⏱ timed out after {limit:.1?}");
+        }
+    }
+
+    if output.lines.is_empty() && output.timed_out.is_none() {
+        println!("Not solved.");
+    }
+}
+
+/// The timings of a day, if it printed any output.
+fn day_timing(run: &DayRun, is_timed: bool, options: RunOptions) -> Option<Timing> {
+    let output = run.output.as_ref()?;
+    if output.lines.is_empty() && output.timed_out.is_none() {
+        return None;
+    }
+
+    let mut val = child_commands::parse_exec_time(&output.lines, run.day);
+    #[allow(clippy::cast_precision_loss)]
+    let timed_out_nanos = output.timed_out.map(|limit| limit.as_nanos() as f64);
+    val.timed_out_nanos = timed_out_nanos;
+    if is_timed && options.allocations == AllocationCounting::Dhat {
+        let allocations = child_commands::count_allocations(run.day).unwrap();
+        val.set_allocations(&allocations);
+    }
+    Some(val)
+}
+
+/// Runs `run` for each day on up to `jobs` threads.
+/// `report` is called on the calling thread in day order, as soon as a day and all days before it are done.
+fn run_parallel<T: Send>(
+    days: &[Day],
+    jobs: usize,
+    run: impl Fn(Day) -> T + Sync,
+    mut report: impl FnMut(usize, T),
+) {
+    let next_day = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(days.len()) {
+            let sender = sender.clone();
+            let (next_day, run) = (&next_day, &run);
+            scope.spawn(move || loop {
+                let i = next_day.fetch_add(1, Ordering::Relaxed);
+                let Some(day) = days.get(i) else {
+                    break;
+                };
+                if sender.send((i, run(*day))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // days finish out of order, hold them back until it is their turn.
+        let mut finished = BTreeMap::new();
+        let mut next_report = 0;
+        for (i, result) in receiver {
+            finished.insert(i, result);
+            while let Some(result) = finished.remove(&next_report) {
+                report(next_report, result);
+                next_report += 1;
+            }
+        }
+    });
+}
+
+/// Lists the days that ran with their outcome and wall-clock time.
+fn print_summary(report: &Report, jobs: usize) {
+    println!("\n{ANSI_BOLD}Summary{ANSI_RESET}");
+    println!("-------");
+
+    for day in &report.days {
+        let status = match day.outcome {
+            Outcome::Passed => "✔ passed",
+            Outcome::Failed => "✖ failed",
+            Outcome::TimedOut => "⏱ timed out",
+            Outcome::Skipped => "⏱ skipped",
+            Outcome::NotSolved => continue,
+        };
         println!(
-            "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
+            "Day {}: {status} {ANSI_ITALIC}({:.1?}){ANSI_RESET}",
+            day.day, day.elapsed
         );
-        Some(timings)
-    } else {
-        None
     }
+
+    let outcomes: Vec<Outcome> = report.days.iter().map(|day| day.outcome).collect();
+    let jobs = if jobs == 1 {
+        "1 job".to_string()
+    } else {
+        format!("{jobs} jobs")
+    };
+    println!(
+        "{} {ANSI_ITALIC}in {:.1?} ({jobs}){ANSI_RESET}",
+        summarize(&outcomes),
+        report.elapsed
+    );
 }
 
 #[allow(dead_code)]
@@ -67,33 +400,114 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::{get_path_for_bin, Error, RunOptions};
+    use crate::template::alloc::AllocStats;
+    use crate::template::timings::PartTiming;
+    use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
     use std::{
         io::{BufRead, BufReader},
         path::Path,
         process::{Command, Stdio},
         thread,
+        time::{Duration, Instant},
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
-        // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+    /// The stdout lines of a solution run.
+    pub struct Output {
+        pub lines: Vec<String>,
+        /// The stderr lines, if they were held back instead of printed as they came in.
+        pub stderr: Vec<String>,
+        /// Whether the day compiled and its bin exited successfully.
+        pub success: bool,
+        /// The time the run was given, if it was stopped before it finished.
+        pub timed_out: Option<Duration>,
+    }
+
+    impl Output {
+        /// Prints output that was held back.
+        pub fn print(&self) {
+            self.stderr.iter().for_each(|line| eprintln!("{line}"));
+            self.lines.iter().for_each(|line| println!("{line}"));
         }
+    }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+    /// Arguments of a cargo command for the bin of a given day.
+    fn cargo_args(command: &str, day: Day, is_release: bool, options: RunOptions) -> Vec<String> {
+        let mut args = vec![
+            command.to_string(),
+            "--quiet".into(),
+            "--bin".into(),
+            day.to_string(),
+        ];
 
         if is_release {
-            args.push("--release");
+            args.push("--release".into());
+        }
+
+        let features = options.features().join(",");
+        if !features.is_empty() {
+            args.extend(["--features".into(), features]);
+        }
+
+        args
+    }
+
+    /// Run the solution bin for a given day.
+    /// With a timeout or a `budget_end`, the bin is compiled first and stopped once its time is up.
+    /// With `echo`, its output is printed as it comes in, otherwise it is held back in the returned [`Output`].
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        options: RunOptions,
+        budget_end: Option<Instant>,
+        echo: bool,
+    ) -> Result<Output, Error> {
+        // skip command invocation for days that have not been scaffolded yet.
+        if !Path::new(&get_path_for_bin(day)).exists() {
+            return Ok(Output {
+                lines: vec![],
+                stderr: vec![],
+                success: true,
+                timed_out: None,
+            });
         }
 
+        let mut build_errors = vec![];
+        let is_limited = options.timeout.is_some() || budget_end.is_some();
+        if is_limited {
+            // compile up front, so the time limit only applies to running the solution.
+            let mut build = Command::new("cargo");
+            build.args(cargo_args("build", day, is_release, options));
+            if echo {
+                build.status()?;
+            } else {
+                let output = build.stderr(Stdio::piped()).output()?;
+                build_errors = String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .map(String::from)
+                    .collect();
+            }
+        }
+
+        let started = Instant::now();
+        let deadline = [options.timeout.map(|timeout| started + timeout), budget_end]
+            .into_iter()
+            .flatten()
+            .min();
+
+        let mut args = cargo_args("run", day, is_release, options);
+        args.push("--".into());
+
         if is_timed {
             // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
+            args.push("--time".into());
+        }
+
+        if let Some(deadline) = deadline {
+            // lets the runner stop benching before the day runs out of time.
+            args.push("--timeout".into());
+            args.push(format!("{:?}", deadline.saturating_duration_since(started)));
         }
 
         // spawn child command with piped stdout/stderr.
@@ -108,85 +522,360 @@ pub mod child_commands {
         let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
         let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
 
-        let mut output = vec![];
+        let stderr_thread = thread::spawn(move || {
+            stderr
+                .lines()
+                .filter_map(|line| {
+                    let line = line.unwrap();
+                    if echo {
+                        eprintln!("{line}");
+                        return None;
+                    }
+                    Some(line)
+                })
+                .collect::<Vec<String>>()
+        });
 
-        let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
-            });
+        let stdout_thread = thread::spawn(move || {
+            stdout
+                .lines()
+                .map(|line| {
+                    let line = line.unwrap();
+                    if echo {
+                        println!("{line}");
+                    }
+                    line
+                })
+                .collect::<Vec<String>>()
         });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
+        let (success, timed_out) = match deadline {
+            None => (cmd.wait()?.success(), None),
+            Some(deadline) => loop {
+                if let Some(status) = cmd.try_wait()? {
+                    break (status.success(), None);
+                }
+                if Instant::now() >= deadline {
+                    // `cargo run` replaces itself with the bin, so this stops the solution.
+                    cmd.kill()?;
+                    cmd.wait()?;
+                    break (false, Some(deadline.saturating_duration_since(started)));
+                }
+                thread::sleep(Duration::from_millis(10));
+            },
+        };
+
+        let lines = stdout_thread.join().unwrap();
+        build_errors.extend(stderr_thread.join().unwrap());
+
+        Ok(Output {
+            lines,
+            stderr: build_errors,
+            success,
+            timed_out,
+        })
+    }
+
+    /// The parts of a stopped run that did not print their final result, e.g. `Part 2`.
+    /// `Parse` is only included if the day started parsing.
+    pub fn unfinished_parts(lines: &[String]) -> Vec<&'static str> {
+        let is_finished = |label: &str| {
+            lines.iter().any(|line| {
+                line.contains(&format!("{label}:")) && (line.ends_with(')') || line.ends_with(']'))
+            })
+        };
+        let is_started = |label: &str| lines.iter().any(|line| line.contains(&format!("{label}:")));
+
+        ["Parse", "Part 1", "Part 2"]
+            .into_iter()
+            .filter(|label| *label != "Parse" || is_started(label))
+            .filter(|label| !is_finished(label))
+            .collect()
+    }
+
+    /// A part's result as printed by the runner.
+    pub struct PrintedPart {
+        pub label: &'static str,
+        /// Whether the part returned a result.
+        pub solved: bool,
+        /// The result, `None` for `Parse`, which only prints whether it succeeded.
+        pub answer: Option<String>,
+        /// The printed duration, the mean if the part was benched.
+        pub duration: Option<String>,
+    }
+
+    /// Parses the final result line of each part, e.g. `Part 1: 42 (1.2ms)`.
+    /// Answers spanning several lines are printed below their part, up to the next part.
+    pub fn parse_parts(lines: &[String]) -> Vec<PrintedPart> {
+        // results are printed over the intermediate output of a part, only the final text counts.
+        let lines: Vec<&str> = lines
+            .iter()
+            .map(|line| line.rsplit('\r').next().unwrap_or(line).trim_end())
+            .collect();
+        let label_of = |line: &str| {
+            ["Parse", "Part 1", "Part 2"]
+                .into_iter()
+                .find(|label| line.starts_with(&format!("{label}: ")))
+        };
+        let is_counters = |line: &str| line.starts_with(&format!("  {ANSI_ITALIC}counters:"));
+
+        let mut parts = vec![];
+        for (i, line) in lines.iter().enumerate() {
+            let Some(label) = label_of(line) else {
+                continue;
+            };
+            let result = &line[label.len() + 2..];
+
+            if result.starts_with('✖') {
+                parts.push(PrintedPart {
+                    label,
+                    solved: false,
+                    answer: None,
+                    duration: None,
+                });
+                continue;
+            }
+
+            let Some(duration) = printed_duration(result) else {
+                // the part did not finish.
+                continue;
+            };
+
+            let answer = if label == "Parse" {
+                None
+            } else if result.starts_with('▼') {
+                let answer: Vec<&str> = lines[i + 1..]
+                    .iter()
+                    .take_while(|line| label_of(line).is_none() && !is_counters(line))
+                    .copied()
+                    .collect();
+                Some(answer.join("\n"))
+            } else {
+                result
+                    .strip_prefix(ANSI_BOLD)
+                    .and_then(|result| result.rsplit_once(ANSI_RESET))
+                    .map(|(answer, _)| answer.to_string())
+            };
+
+            parts.push(PrintedPart {
+                label,
+                solved: true,
+                answer: answer.map(|answer| strip_ansi(&answer)),
+                duration: Some(duration.to_string()),
+            });
+        }
+
+        parts
+    }
+
+    /// A variant of a part as printed by the runner, see [`run_variants`](crate::template::runner::run_variants).
+    pub struct PrintedVariant {
+        /// The part the variant belongs to, e.g. `Part 1`.
+        pub part: &'static str,
+        pub name: String,
+        pub answer: Option<String>,
+        pub duration: Option<String>,
+        pub agrees: bool,
+    }
+
+    /// Parses the variants listed below `Part 1 variants:`, the baseline first.
+    pub fn parse_variants(lines: &[String]) -> Vec<PrintedVariant> {
+        let mut part = None;
+        let mut variants = vec![];
+
+        for line in lines {
+            let line = strip_ansi(line.rsplit('\r').next().unwrap_or(line));
+            let line = line.trim_end();
+
+            if let Some(header) = line.strip_suffix(" variants:") {
+                part = ["Part 1", "Part 2"]
+                    .into_iter()
+                    .find(|label| *label == header);
+                continue;
+            }
+            let (Some(current), Some(variant)) = (part, line.strip_prefix("  ")) else {
+                part = None;
+                continue;
+            };
+            // the counters of a variant, which can end in a parenthesis like a result does.
+            if variant.starts_with("counters:") {
+                continue;
+            }
+            let Some((name, result)) = variant.split_once(": ") else {
+                continue;
+            };
+
+            // the comparison with the baseline follows the duration, e.g. ` · 1.20× faster`.
+            let (result, comparison) = match result.trim_start().rsplit_once(" · ") {
+                Some((result, comparison)) if !comparison.contains(')') => (result, comparison),
+                _ => (result.trim_start(), ""),
+            };
+            let Some(duration) = printed_duration(result) else {
+                continue;
+            };
+            let answer = result
+                .rsplit_once(" [")
+                .filter(|_| result.ends_with(']'))
+                .map_or(result, |(result, _)| result)
+                .rsplit_once(" (")
+                .map(|(answer, _)| answer)
+                .filter(|answer| *answer != "✖");
+
+            variants.push(PrintedVariant {
+                part: current,
+                name: name.to_string(),
+                answer: answer.map(String::from),
+                duration: Some(duration.to_string()),
+                agrees: !comparison.starts_with('✖'),
+            });
+        }
+
+        variants
+    }
+
+    /// The duration in `(<duration>)` or `(<mean> @ <n> samples, …)`, optionally followed by `[<allocations>]`.
+    fn printed_duration(result: &str) -> Option<&str> {
+        let result = match result.strip_suffix(']') {
+            Some(result) => result.rsplit_once(" [")?.0,
+            None => result,
+        };
+        let stats = result.strip_suffix(')')?.rsplit('(').next()?;
+        Some(stats.split(" @").next()?.trim())
+    }
+
+    /// Removes the escape sequences that color terminal output.
+    pub fn strip_ansi(line: &str) -> String {
+        let mut stripped = String::with_capacity(line.len());
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                // skip up to the letter that ends the sequence, e.g. `\x1b[1m`.
+                chars.find(char::is_ascii_alphabetic);
+            } else {
+                stripped.push(c);
+            }
+        }
+        stripped
+    }
+
+    /// Run the solution bin for a given day once with the dhat heap profiler.
+    /// Returns the number of allocations of each part that was run, by label.
+    pub fn count_allocations(day: Day) -> Result<Vec<(String, u64)>, Error> {
+        eprintln!("{ANSI_ITALIC}Counting allocations…{ANSI_RESET}");
+
+        let day_padded = day.to_string();
+        let output = Command::new("cargo")
+            .args(["run", "--quiet", "--bin", &day_padded])
+            .args(["--profile", "dhat", "--features", "dhat-heap"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()?;
+
+        let allocations = parse_allocations(&String::from_utf8_lossy(&output.stderr));
+        if allocations.is_empty() {
+            eprintln!("Could not count allocations for day {day}.");
         }
+        Ok(allocations)
+    }
 
-        thread.join().unwrap();
-        cmd.wait()?;
+    /// Parses the `dhat: Total: 1,256 bytes in 6 blocks` summary printed after each part,
+    /// which follows the `dhat: profiling Part 1` line printed by the runner.
+    pub fn parse_allocations(stderr: &str) -> Vec<(String, u64)> {
+        let mut label = None;
+        let mut allocations = vec![];
+
+        for line in stderr.lines() {
+            if let Some(profiled) = line.strip_prefix("dhat: profiling ") {
+                label = Some(profiled.trim().to_string());
+            } else if let Some(total) = line.strip_prefix("dhat: Total:") {
+                let blocks = total
+                    .split(" in ")
+                    .nth(1)
+                    .and_then(|blocks| blocks.strip_suffix(" blocks"))
+                    .and_then(|blocks| blocks.replace(',', "").trim().parse().ok());
+                if let (Some(label), Some(blocks)) = (label.take(), blocks) {
+                    allocations.push((label, blocks));
+                }
+            }
+        }
 
-        Ok(output)
+        allocations
     }
 
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
         let mut timings = super::Timing {
             day,
+            parse: None,
             part_1: None,
             part_2: None,
             total_nanos: 0_f64,
+            timed_out_nanos: None,
         };
 
         output
             .iter()
             .filter_map(|l| {
-                if !l.contains(" samples)") {
+                if !l.contains(" samples)") && !l.contains(" samples, median ") {
                     return None;
                 }
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
+                // variants of a part are benched as well, only the parts count towards the day.
+                let part = l.split(':').next()?;
+                if !["Parse", "Part 1", "Part 2"]
+                    .iter()
+                    .any(|label| part.contains(label))
+                {
+                    return None;
+                }
+
+                let Some(timing) = parse_time(l) else {
                     eprintln!("Could not parse timings from line: {l}");
                     return None;
                 };
 
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
+                Some((part, timing))
             })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
+            .for_each(|(part, timing)| {
+                timings.total_nanos += timing.nanos;
+
+                if part.contains("Parse") {
+                    timings.parse = Some(timing);
+                } else if part.contains("Part 1") {
+                    timings.part_1 = Some(timing);
                 } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
+                    timings.part_2 = Some(timing);
                 }
-
-                timings.total_nanos += nanos;
             });
 
         timings
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
-    }
+    /// Parses `(<mean> @ <n> samples)`, optionally followed by `, median <median> ± <spread>`
+    /// inside the parentheses and by `[<allocations>]` after them.
+    fn parse_time(line: &str) -> Option<PartTiming> {
+        let (line, allocations) = match line
+            .strip_suffix(']')
+            .and_then(|line| line.rsplit_once(" ["))
+        {
+            Some((line, allocations)) => (line, Some(allocations.parse::<AllocStats>().ok()?)),
+            None => (line, None),
+        };
+
+        let stats = line.rsplit('(').next()?.strip_suffix(')')?;
+        let (str_timing, rest) = stats.split_once('@')?;
+        let (samples, median) = rest.split_once(" samples")?;
+
+        let mut timing = PartTiming::from_duration(str_timing.trim(), samples.trim().parse().ok())?;
 
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
-            .split(" samples)")
-            .next()?
-            .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
-
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
+        if let Some(median) = median.strip_prefix(", median ") {
+            let (median, spread) = median.split_once('±')?;
+            timing = timing.with_median(median.trim(), spread.trim());
+        }
+
+        match allocations {
+            Some(allocations) => Some(timing.with_allocations(allocations)),
+            None => Some(timing),
+        }
     }
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
@@ -205,7 +894,10 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{
+            parse_allocations, parse_exec_time, parse_parts, parse_variants, strip_ansi,
+            unfinished_parts,
+        };
 
         use crate::day;
 
@@ -220,8 +912,149 @@ pub mod child_commands {
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+            assert_eq!(res.part_1.as_ref().unwrap().duration, "74.13ns");
+            assert_eq!(res.part_1.as_ref().unwrap().samples, Some(100000));
+            assert_eq!(res.part_2.as_ref().unwrap().duration, "74.13ms");
+        }
+
+        #[test]
+        fn parses_allocations() {
+            let stderr = [
+                "dhat: profiling Parse",
+                "dhat: Total:     288 bytes in 4 blocks",
+                "dhat: At t-gmax: 224 bytes in 3 blocks",
+                "dhat: profiling Part 2",
+                "dhat: Total:     1,428,000 bytes in 12,004 blocks",
+            ]
+            .join("\n");
+            assert_eq!(
+                parse_allocations(&stderr),
+                [("Parse".to_string(), 4), ("Part 2".to_string(), 12004)]
+            );
+        }
+
+        #[test]
+        fn parses_medians() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 42 (74.1ns @ 10000 samples, median 70.0ns ± 2.5ns)".into(),
+                    "Part 2: (1) (1.5ms @ 667 samples, median 1.4ms ± 20.0µs)".into(),
+                ],
+                day!(1),
+            );
+            let part_1 = res.part_1.unwrap();
+            assert_eq!(part_1.samples, Some(10000));
+            assert_eq!(part_1.median_nanos, Some(70.0));
+            assert_eq!(part_1.spread_nanos, Some(2.5));
+            assert_eq!(res.part_2.unwrap().median_nanos, Some(1_400_000.0));
+        }
+
+        #[test]
+        fn parses_counted_allocations() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 42 (74.1ns @ 10000 samples, median 70.0ns ± 2.5ns) [3 allocations, 96 bytes, peak 64 bytes]".into(),
+                    "Part 2: [1] (1.5ms @ 667 samples) [0 allocations, 0 bytes, peak 0 bytes]".into(),
+                ],
+                day!(1),
+            );
+            let part_1 = res.part_1.unwrap();
+            assert_eq!(part_1.median_nanos, Some(70.0));
+            assert_eq!(part_1.allocations, Some(3));
+            assert_eq!(part_1.bytes, Some(96));
+            assert_eq!(part_1.peak_bytes, Some(64));
+            let part_2 = res.part_2.unwrap();
+            assert_eq!(part_2.duration, "1.5ms");
+            assert_eq!(part_2.allocations, Some(0));
+        }
+
+        #[test]
+        fn finds_unfinished_parts() {
+            let lines = [
+                "Part 1: 6 (34.0ns @ 10000 samples, median 32.0ns ± 1.0ns)".to_string(),
+                "Part 2: 42 > benching".to_string(),
+            ];
+            assert_eq!(unfinished_parts(&lines), ["Part 2"]);
+            assert_eq!(
+                unfinished_parts(&["Parse: ✔ > benching".to_string()]),
+                ["Parse", "Part 1", "Part 2"]
+            );
+            assert_eq!(unfinished_parts(&[]), ["Part 1", "Part 2"]);
+        }
+
+        #[test]
+        fn parses_printed_parts() {
+            let lines = [
+                "Parse: \x1b[1m✔\x1b[0m\rParse: \x1b[1m✔\x1b[0m (2.0ms @ 500 samples, median 1.9ms ± 10.0µs)".to_string(),
+                "Part 1: \x1b[1m(1)\x1b[0m (1.5ms) [0 allocations, 0 bytes, peak 0 bytes]".to_string(),
+                "Part 2: ▼ (3.0µs)".to_string(),
+                "#..#".to_string(),
+                "####".to_string(),
+            ];
+            let parts = parse_parts(&lines);
+
+            assert_eq!(parts.len(), 3);
+            assert_eq!(parts[0].label, "Parse");
+            assert_eq!(parts[0].answer, None);
+            assert_eq!(parts[0].duration.as_deref(), Some("2.0ms"));
+            assert_eq!(parts[1].answer.as_deref(), Some("(1)"));
+            assert_eq!(parts[1].duration.as_deref(), Some("1.5ms"));
+            assert_eq!(parts[2].answer.as_deref(), Some("#..#\n####"));
+        }
+
+        #[test]
+        fn parses_unsolved_and_unfinished_parts() {
+            let parts = parse_parts(&[
+                "Part 1: ✖\rPart 1: ✖             ".to_string(),
+                "Part 2: \x1b[1m42\x1b[0m > benching".to_string(),
+            ]);
+            assert_eq!(parts.len(), 1);
+            assert!(!parts[0].solved);
+            assert_eq!(parts[0].duration, None);
+        }
+
+        #[test]
+        fn parses_variants() {
+            let variants = parse_variants(&[
+                "Part 1: \x1b[1m3749\x1b[0m (2.0ms @ 500 samples, median 1.9ms ± 10.0µs)".into(),
+                "Part 1 variants:".into(),
+                "  part_one:          \r  part_one:          \x1b[1m3749\x1b[0m (2.0ms @ 500 samples, median 1.9ms ± 10.0µs)".into(),
+                "  part_one_backward: \x1b[1m3749\x1b[0m (1.0ms @ 1000 samples, median 1.0ms ± 5.0µs) · \x1b[3m2.00× faster\x1b[0m".into(),
+                "  \x1b[3mcounters: 1.24M instructions, 930.1k cycles (1.33 IPC)\x1b[0m".into(),
+                "  part_one_broken:   ✖ (1.0ms @ 1000 samples, median 1.0ms ± 5.0µs) · \x1b[1m✖ disagrees with part_one\x1b[0m".into(),
+                "Part 2: ✖ ".into(),
+                "  part_two: 1 (1.0ms)".into(),
+            ]);
+            assert_eq!(variants.len(), 3);
+            assert_eq!(variants[0].part, "Part 1");
+            assert_eq!(variants[0].name, "part_one");
+            assert_eq!(variants[0].answer.as_deref(), Some("3749"));
+            assert_eq!(variants[1].duration.as_deref(), Some("1.0ms"));
+            assert!(variants[1].agrees);
+            assert_eq!(variants[2].answer, None);
+            assert!(!variants[2].agrees);
+        }
+
+        #[test]
+        fn strips_ansi_codes() {
+            assert_eq!(
+                strip_ansi("\x1b[1mDay 01\x1b[0m: \x1b[3m(1.0ms)\x1b[0m"),
+                "Day 01: (1.0ms)"
+            );
+        }
+
+        #[test]
+        fn parses_parse_times() {
+            let res = parse_exec_time(
+                &[
+                    "Parse: ✔ (2.0ms @ 500 samples, median 1.9ms ± 10.0µs)".into(),
+                    "Part 1: 18 (1.0ms @ 1000 samples)".into(),
+                ],
+                day!(4),
+            );
+            assert_eq!(res.parse.unwrap().duration, "2.0ms");
+            assert_eq!(res.part_1.unwrap().duration, "1.0ms");
+            assert_approx_eq!(res.total_nanos, 3_000_000_f64);
         }
 
         #[test]
@@ -235,8 +1068,8 @@ pub mod child_commands {
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+            assert_eq!(res.part_1.as_ref().unwrap().duration, "2s");
+            assert_eq!(res.part_2.as_ref().unwrap().duration, "100ms");
         }
 
         #[test]
@@ -255,3 +1088,31 @@ pub mod child_commands {
         }
     }
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::run_parallel;
+    use crate::day;
+    use std::{thread, time::Duration};
+
+    #[test]
+    fn reports_parallel_days_in_order() {
+        let days = [day!(1), day!(2), day!(3), day!(4), day!(5)];
+        let mut reported = vec![];
+
+        run_parallel(
+            &days,
+            3,
+            |day| {
+                // earlier days take longer, so they finish last.
+                thread::sleep(Duration::from_millis(
+                    10 * (6 - u64::from(day.into_inner())),
+                ));
+                day
+            },
+            |i, day| reported.push((i, day)),
+        );
+
+        assert_eq!(reported, days.into_iter().enumerate().collect::<Vec<_>>());
+    }
+}
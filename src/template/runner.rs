@@ -1,58 +1,324 @@
-/// Encapsulates code that interacts with solution functions.
+//! Encapsulates code that interacts with solution functions.
+
 use std::fmt::Display;
+use std::fs;
 use std::hint::black_box;
 use std::io::{stdout, Write};
+use std::path::Path;
 use std::process::Output;
+use std::sync::{Once, OnceLock};
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::template::alloc::AllocStats;
+use crate::template::compare::input_files;
+use crate::template::input::InputSource;
+use crate::template::perf::{CounterStats, Counters};
+use crate::template::timings::parse_duration;
+use crate::template::validate::{Diagnostic, Severity, Validator};
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_cli, ledger, Day, ANSI_ITALIC, ANSI_RESET};
 
 pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, stats) = run_timed(func, input, &part_str, |result| {
+        print_result(result, &part_str, "");
+    });
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    print_result(&result, &part_str, &format_stats(&stats));
+    print_counters(&stats);
 
     if let Some(result) = result {
-        submit_result(result, day, part);
+        let answer = result.to_string();
+        if let Some(Ok(output)) = submit_result(result, day, part) {
+            if aoc_cli::is_accepted(&output) {
+                match ledger::record(day, part, &answer) {
+                    Ok(()) => println!("Recorded the answer for `cargo stars`."),
+                    Err(e) => eprintln!("Could not record the answer: {e}"),
+                }
+            }
+        }
+    }
+}
+
+/// Run a day's `parse` function, timed separately from the parts it feeds.
+/// Returns `None` if the input could not be parsed.
+pub fn run_parse<'a, T>(func: impl Fn(&'a str) -> Option<T>, input: &'a str) -> Option<T> {
+    let label = "Parse";
+    let status = |parsed: &Option<T>| parsed.as_ref().map(|_| "✔");
+
+    let (parsed, stats) = run_timed(func, input, label, |parsed| {
+        print_result(&status(parsed), label, "");
+    });
+
+    print_result(&status(&parsed), label, &format_stats(&stats));
+    print_counters(&stats);
+    parsed
+}
+
+/// Validates the input before it is solved. With `--check`, every diagnostic is printed and the
+/// run ends after the check. Otherwise only errors are printed, and only if there are any,
+/// in which case the input is not solved.
+pub fn run_check(input: &str, day: Day, validate: impl Fn(&mut Validator)) {
+    let diagnostics = diagnose(input, &validate);
+    let errors = count_errors(&diagnostics);
+
+    if env::args().any(|x| x == "--check") {
+        print_diagnostics(input, &diagnostics);
+        process::exit(i32::from(errors > 0));
+    }
+
+    if errors > 0 {
+        print_errors(input, &diagnostics);
+        eprintln!(
+            "The input has {}, run `cargo check-input {day}` to see all diagnostics.",
+            plural(errors, "error")
+        );
+        process::exit(1);
+    }
+}
+
+/// Like [`run_check`], for every `*.txt` file in `dir`. No input is solved if any of them has errors.
+pub fn run_check_inputs(dir: &Path, day: Day, validate: impl Fn(&mut Validator)) {
+    let exit_with = |e: String| -> ! {
+        eprintln!("Error: {e}");
+        process::exit(1);
+    };
+    let is_check = env::args().any(|x| x == "--check");
+    let mut invalid = 0;
+
+    for path in input_files(dir).unwrap_or_else(|e| exit_with(e)) {
+        let input = fs::read_to_string(&path).unwrap_or_else(|e| {
+            exit_with(format!("could not read input \"{}\": {e}", path.display()))
+        });
+        let diagnostics = diagnose(&input, &validate);
+        let errors = count_errors(&diagnostics);
+
+        if is_check {
+            println!("{ANSI_BOLD}{}{ANSI_RESET}", path.display());
+            print_diagnostics(&input, &diagnostics);
+            println!();
+        } else if errors > 0 {
+            eprintln!("{ANSI_BOLD}{}{ANSI_RESET}", path.display());
+            print_errors(&input, &diagnostics);
+        }
+        if errors > 0 {
+            invalid += 1;
+        }
+    }
+
+    if is_check {
+        process::exit(i32::from(invalid > 0));
+    }
+
+    if invalid > 0 {
+        eprintln!(
+            "{} with errors, run `cargo check-input {day} --input {}` to see all diagnostics.",
+            plural(invalid, "input"),
+            dir.display()
+        );
+        process::exit(1);
+    }
+}
+
+fn diagnose(input: &str, validate: &impl Fn(&mut Validator)) -> Vec<Diagnostic> {
+    let mut validator = Validator::new(input);
+    validate(&mut validator);
+    validator.finish()
+}
+
+fn count_errors(diagnostics: &[Diagnostic]) -> usize {
+    diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .count()
+}
+
+/// Prints every diagnostic, followed by how many errors and warnings there are.
+fn print_diagnostics(input: &str, diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        println!("{}\n", diagnostic.render(input));
+    }
+    let errors = count_errors(diagnostics);
+    if diagnostics.is_empty() {
+        println!("✔ No problems found.");
+    } else {
+        println!(
+            "{}, {}",
+            plural(errors, "error"),
+            plural(diagnostics.len() - errors, "warning")
+        );
     }
 }
 
+fn print_errors(input: &str, diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+    {
+        eprintln!("{}\n", diagnostic.render(input));
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{count} {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+/// A named implementation of a part, see [`run_variants`].
+pub type Variant<'a, I, T> = (&'a str, &'a dyn Fn(I) -> Option<T>);
+
+/// Run alternative implementations of a part next to each other, the first one being the baseline.
+/// Each variant's duration is printed with its speedup over the baseline.
+/// Returns `false` if a variant's answer differs from the baseline's.
+pub fn run_variants<I: Clone, T: Display>(
+    part: &str,
+    input: I,
+    variants: &[Variant<I, T>],
+) -> bool {
+    let part_str = match part {
+        "part_one" => "Part 1".to_string(),
+        "part_two" => "Part 2".to_string(),
+        part => part.to_string(),
+    };
+    println!("{part_str} variants:");
+
+    let width = variants
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let mut baseline: Option<(&str, Option<String>, Duration)> = None;
+    let mut agree = true;
+
+    for (name, func) in variants {
+        let label = format!("  {:<width$}", format!("{name}:"));
+        let (result, stats) = run_timed(func, input.clone(), name, |_| print!("{label}"));
+        let answer = result.map(|result| result.to_string());
+
+        let comparison = match &baseline {
+            None => String::new(),
+            Some((baseline_name, expected, _)) if *expected != answer => {
+                agree = false;
+                format!(" · {ANSI_BOLD}✖ disagrees with {baseline_name}{ANSI_RESET}")
+            }
+            Some((_, _, baseline_mean)) => {
+                let speedup = baseline_mean.as_secs_f64() / stats.mean.as_secs_f64();
+                format!(" · {ANSI_ITALIC}{}{ANSI_RESET}", format_speedup(speedup))
+            }
+        };
+
+        let shown = match &answer {
+            Some(answer) if answer.contains('\n') => "▼".to_string(),
+            Some(answer) => format!("{ANSI_BOLD}{answer}{ANSI_RESET}"),
+            None => "✖".to_string(),
+        };
+        print!("\r");
+        println!("{label} {shown}{}{comparison}", format_stats(&stats));
+        print_counters(&stats);
+
+        if baseline.is_none() {
+            baseline = Some((name, answer, stats.mean));
+        }
+    }
+
+    if !agree {
+        eprintln!("The variants of {part_str} do not agree on the answer.");
+    }
+    agree
+}
+
+/// Formats how many times faster a variant is than the baseline, e.g. `3.20× faster` or `1.50× slower`.
+pub(crate) fn format_speedup(speedup: f64) -> String {
+    if !speedup.is_normal() {
+        return "no measurable difference".into();
+    }
+    if speedup >= 1.0 {
+        format!("{speedup:.2}× faster")
+    } else {
+        format!("{:.2}× slower", 1.0 / speedup)
+    }
+}
+
+/// Execution times of a solution part.
+struct Stats {
+    mean: Duration,
+    median: Duration,
+    /// Median absolute deviation from the median.
+    spread: Duration,
+    /// Number of bench samples, `None` if the part was run once without benching.
+    samples: Option<u128>,
+    /// Allocations of the first run, only counted with the `count-allocations` feature.
+    allocations: Option<AllocStats>,
+    /// Hardware counters per benched run, only read with the `perf-counters` feature.
+    counters: Option<CounterStats>,
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///     Benching is cut short if the run was given a `--timeout`, see [`bench_deadline`].
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
+    #[allow(unused_variables)] label: &str,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> (T, Stats) {
+    // starts the clock of the bench budget with the first part.
+    bench_deadline();
+
     let timer = Instant::now();
-    let result = {
+    let (result, allocations) = {
         let input = input.clone();
 
+        // the label tells `cargo time --dhat` which part the following totals belong to.
+        #[cfg(feature = "dhat-heap")]
+        eprintln!("dhat: profiling {label}");
         #[cfg(feature = "dhat-heap")]
         let _profiler = dhat::Profiler::new_heap();
 
-        func(input)
+        count_allocations(|| func(input))
     };
     let base_time = timer.elapsed();
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+    let stats = if std::env::args().any(|x| x == "--time") {
+        Stats {
+            allocations,
+            ..bench(func, input, &base_time)
+        }
     } else {
-        (base_time, 1)
+        Stats {
+            mean: base_time,
+            median: base_time,
+            spread: Duration::ZERO,
+            samples: None,
+            allocations,
+            counters: None,
+        }
     };
 
-    (result, run.0, run.1)
+    (result, stats)
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+#[cfg(all(feature = "count-allocations", not(feature = "dhat-heap")))]
+fn count_allocations<T>(func: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    let (result, allocations) = crate::template::alloc::measure(func);
+    (result, Some(allocations))
+}
+
+#[cfg(not(all(feature = "count-allocations", not(feature = "dhat-heap"))))]
+fn count_allocations<T>(func: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    (func(), None)
+}
+
+fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> Stats {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
@@ -62,20 +328,77 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
 
     let mut timers: Vec<Duration> = vec![];
+    let mut counters = open_counters();
 
     for _ in 0..bench_iterations {
+        if bench_deadline().is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+
         // need a clone here to make the borrow checker happy.
         let cloned = input.clone();
-        let timer = Instant::now();
-        black_box(func(black_box(cloned)));
-        timers.push(timer.elapsed());
+        let run = || {
+            let timer = Instant::now();
+            black_box(func(black_box(cloned)));
+            timers.push(timer.elapsed());
+        };
+        // counters are started outside the timer, so reading them does not add to the duration.
+        match counters.as_mut() {
+            Some(counters) => counters.measure(run),
+            None => run(),
+        }
+    }
+
+    // without time for a single bench run, the first run is the only sample.
+    if timers.is_empty() {
+        timers.push(*base_time);
     }
 
-    (
+    let median = median_duration(&mut timers);
+    let mut deviations: Vec<Duration> = timers.iter().map(|t| t.abs_diff(median)).collect();
+
+    Stats {
         #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+        mean: Duration::from_nanos(average_duration(&timers) as u64),
+        median,
+        spread: median_duration(&mut deviations),
+        samples: Some(timers.len() as u128),
+        allocations: None,
+        counters: counters.map(|counters| counters.stats()),
+    }
+}
+
+/// With a `--timeout` passed by `cargo time`, benching stops once half of it is used up,
+/// so the remaining parts still have time to run.
+fn bench_deadline() -> Option<Instant> {
+    static DEADLINE: OnceLock<Option<Instant>> = OnceLock::new();
+
+    *DEADLINE.get_or_init(|| {
+        let args: Vec<String> = env::args().collect();
+        let timeout = args
+            .iter()
+            .position(|arg| arg == "--timeout")
+            .and_then(|i| args.get(i + 1))
+            .and_then(|timeout| parse_duration(timeout))?;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Some(Instant::now() + Duration::from_nanos((timeout / 2.0) as u64))
+    })
+}
+
+/// Opens the hardware counters if built with the `perf-counters` feature.
+/// If they are unavailable, parts are only timed and the reason is printed once.
+fn open_counters() -> Option<Counters> {
+    static UNAVAILABLE: Once = Once::new();
+
+    if !cfg!(feature = "perf-counters") {
+        return None;
+    }
+
+    Counters::open()
+        .map_err(|e| {
+            UNAVAILABLE.call_once(|| eprintln!("Hardware counters unavailable, timing only: {e}"));
+        })
+        .ok()
 }
 
 fn average_duration(numbers: &[Duration]) -> u128 {
@@ -86,11 +409,32 @@ fn average_duration(numbers: &[Duration]) -> u128 {
         / numbers.len() as u128
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
-    if samples == 1 {
-        format!(" ({duration:.1?})")
-    } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
+fn median_duration(numbers: &mut [Duration]) -> Duration {
+    numbers.sort_unstable();
+    numbers[numbers.len() / 2]
+}
+
+fn format_stats(stats: &Stats) -> String {
+    let durations = match stats.samples {
+        None => format!(" ({:.1?})", stats.mean),
+        Some(samples) => format!(
+            " ({:.1?} @ {samples} samples, median {:.1?} ± {:.1?})",
+            stats.mean, stats.median, stats.spread
+        ),
+    };
+
+    match stats.allocations {
+        Some(allocations) => format!("{durations} [{allocations}]"),
+        None => durations,
+    }
+}
+
+fn print_counters(stats: &Stats) {
+    if let Some(counters) = stats.counters {
+        let counters = counters.to_string();
+        if !counters.is_empty() {
+            println!("  {ANSI_ITALIC}counters: {counters}{ANSI_RESET}");
+        }
     }
 }
 
@@ -132,6 +476,7 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
+///  3. the solution ran on the puzzle input without `--param` overrides.
 fn submit_result<T: Display>(
     result: T,
     day: Day,
@@ -159,6 +504,13 @@ fn submit_result<T: Display>(
         return None;
     }
 
+    if InputSource::from_args() != InputSource::Puzzle || args.contains(&"--param".into()) {
+        eprintln!(
+            "Error: `--submit` can only be used with the puzzle input and without `--param`."
+        );
+        process::exit(1);
+    }
+
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
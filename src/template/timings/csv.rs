@@ -0,0 +1,179 @@
+//! CSV storage with one row per day. The header row doubles as the schema version:
+//! files are only read if their header holds known columns, with only optional ones missing.
+
+use super::{Field, Timing, Timings, TimingsFormat, FIELDS, OPTIONAL_FIELDS};
+
+pub struct CsvFormat;
+
+fn escape(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Splits a CSV row into its cells, handling quoted cells.
+fn split_row(row: &str) -> Result<Vec<String>, String> {
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = row.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if cell.is_empty() => quoted = true,
+            (',', false) => cells.push(std::mem::take(&mut cell)),
+            (c, _) => cell.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("unterminated quoted cell.".into());
+    }
+    cells.push(cell);
+    Ok(cells)
+}
+
+impl TimingsFormat for CsvFormat {
+    fn serialize(&self, timings: &Timings) -> String {
+        let mut lines = vec![FIELDS.join(",")];
+
+        for timing in &timings.data {
+            let cells: Vec<String> = timing
+                .to_fields()
+                .into_iter()
+                .map(|field| match field {
+                    Some(Field::Text(value) | Field::Number(value)) => escape(&value),
+                    None => String::new(),
+                })
+                .collect();
+            lines.push(cells.join(","));
+        }
+
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    fn deserialize(&self, contents: &str) -> Result<Timings, String> {
+        let mut rows = contents.lines().filter(|line| !line.trim().is_empty());
+
+        let header = rows.next().ok_or("expected a header row.")?;
+        let columns = split_row(header)?;
+        let is_compatible = columns
+            .iter()
+            .all(|column| FIELDS.contains(&column.as_str()))
+            && FIELDS.iter().all(|field| {
+                OPTIONAL_FIELDS.contains(field) || columns.contains(&field.to_string())
+            });
+        if !is_compatible {
+            return Err(format!(
+                "expected header `{}`, found `{header}`.",
+                FIELDS.join(",")
+            ));
+        }
+
+        let data = rows
+            .map(|row| {
+                let cells = split_row(row)?;
+                if cells.len() != columns.len() {
+                    return Err(format!(
+                        "expected {} cells, found {}.",
+                        columns.len(),
+                        cells.len()
+                    ));
+                }
+                Timing::from_fields(|name| {
+                    let column = columns.iter().position(|column| column == name)?;
+                    Some(cells[column].as_str()).filter(|cell| !cell.is_empty())
+                })
+            })
+            .enumerate()
+            .map(|(index, timing)| timing.map_err(|e| format!("row {}: {e}", index + 2)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Timings { data })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{split_row, CsvFormat};
+    use crate::day;
+    use crate::template::timings::{PartTiming, Timing, Timings, TimingsFormat};
+
+    #[test]
+    fn round_trips_timings() {
+        let timings = Timings {
+            data: vec![
+                Timing {
+                    day: day!(1),
+                    parse: None,
+                    part_1: PartTiming::from_duration("74.1ns", Some(10000)),
+                    part_2: None,
+                    total_nanos: 74.1,
+                    timed_out_nanos: None,
+                },
+                Timing {
+                    day: day!(2),
+                    parse: None,
+                    part_1: PartTiming::from_duration("1.2s", None),
+                    part_2: PartTiming::from_duration("2.5ms", Some(10)),
+                    total_nanos: 1_202_500_000.0,
+                    timed_out_nanos: None,
+                },
+            ],
+        };
+
+        let csv = CsvFormat.serialize(&timings);
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("01,,,,,,,,,74.1ns,74.1,10000,,,,,,,,,,,,,,74.1,")
+        );
+
+        let parsed = CsvFormat.deserialize(&csv).unwrap();
+        assert_eq!(parsed.data.len(), 2);
+        assert_eq!(parsed.data[1].part_1, timings.data[1].part_1);
+        assert_eq!(parsed.data[1].part_2, timings.data[1].part_2);
+        assert_eq!(parsed.data[0].part_2, None);
+    }
+
+    #[test]
+    fn reports_corrupt_files() {
+        assert!(CsvFormat.deserialize("").is_err());
+        assert!(CsvFormat.deserialize("day,total_nanos\n01,1").is_err());
+
+        let header = CsvFormat.serialize(&Timings::default());
+        let error = CsvFormat
+            .deserialize(&format!("{header}01,,,,,,,,,1ms,,,,,,,,,,,,,,,,1,"))
+            .unwrap_err();
+        assert_eq!(error, "row 2: expected `part_1_nanos` for `part_1`.");
+    }
+
+    #[test]
+    fn reads_files_without_optional_columns() {
+        let csv = "day,part_1,part_1_nanos,part_1_samples,part_2,part_2_nanos,part_2_samples,total_nanos\n\
+                   01,2ms,2000000,500,,,,2000000\n";
+        let parsed = CsvFormat.deserialize(csv).unwrap();
+        assert_eq!(
+            parsed.data[0].part_1,
+            PartTiming::from_duration("2ms", Some(500))
+        );
+    }
+
+    #[test]
+    fn splits_quoted_cells() {
+        assert_eq!(
+            split_row(r#"a,"b,c","d""e""#).unwrap(),
+            ["a", "b,c", "d\"e"]
+        );
+        assert!(split_row(r#"a,"b"#).is_err());
+    }
+}
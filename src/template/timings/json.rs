@@ -0,0 +1,356 @@
+//! JSON storage, the default format of `data/timings.json`.
+
+use std::{collections::HashMap, str::FromStr};
+use tinyjson::JsonValue;
+
+use super::{PartTiming, Timing, Timings, TimingsFormat, SCHEMA_VERSION};
+use crate::template::Day;
+
+pub struct JsonFormat;
+
+impl TimingsFormat for JsonFormat {
+    fn serialize(&self, timings: &Timings) -> String {
+        JsonValue::from(timings.clone())
+            .format()
+            .expect("timings are always valid JSON.")
+    }
+
+    fn deserialize(&self, contents: &str) -> Result<Timings, String> {
+        Timings::try_from(contents.to_string())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Timings> for JsonValue {
+    fn from(value: Timings) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "version".into(),
+            JsonValue::Number(f64::from(SCHEMA_VERSION)),
+        );
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Timings {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let document = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?;
+
+        // files written before versioning was introduced are version 1.
+        let version = match document.get("version") {
+            None => 1.0,
+            Some(version) => version
+                .get::<f64>()
+                .copied()
+                .ok_or("expected `json.version` to be a number.")?,
+        };
+
+        if version > f64::from(SCHEMA_VERSION) {
+            return Err(format!(
+                "schema version {version} is newer than the supported version {SCHEMA_VERSION}."
+            ));
+        }
+
+        let json_data = document
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Timings {
+            data: json_data
+                .iter()
+                .map(|timing| {
+                    if version < 2.0 {
+                        migrate_v1(timing)
+                    } else {
+                        Timing::try_from(timing)
+                    }
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn object(value: &JsonValue) -> Result<&HashMap<String, JsonValue>, String> {
+    value
+        .get::<HashMap<String, JsonValue>>()
+        .ok_or_else(|| "Expected timing to be a JSON object.".into())
+}
+
+fn parse_day(json: &HashMap<String, JsonValue>) -> Result<Day, String> {
+    json.get("day")
+        .and_then(|v| v.get::<String>())
+        .and_then(|day| Day::from_str(day).ok())
+        .ok_or_else(|| "Expected timing.day to be a Day struct.".into())
+}
+
+fn parse_total_nanos(json: &HashMap<String, JsonValue>) -> Result<f64, String> {
+    json.get("total_nanos")
+        .and_then(|v| v.get::<f64>().copied())
+        .ok_or_else(|| "Expected timing.total_nanos to be a number.".into())
+}
+
+/// Version 1 stored parts as the printed duration only, e.g. `"part_1": "74.13ns"`.
+fn migrate_v1(value: &JsonValue) -> Result<Timing, String> {
+    let json = object(value)?;
+
+    let part = |name: &str| -> Result<Option<PartTiming>, String> {
+        let duration = json
+            .get(name)
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or(format!("Expected timing.{name} to be null or string."))?;
+
+        duration
+            .map(|duration| {
+                PartTiming::from_duration(duration, None)
+                    .ok_or(format!("Expected timing.{name} to be a duration."))
+            })
+            .transpose()
+    };
+
+    Ok(Timing {
+        day: parse_day(json)?,
+        parse: None,
+        part_1: part("part_1")?,
+        part_2: part("part_2")?,
+        total_nanos: parse_total_nanos(json)?,
+        timed_out_nanos: None,
+    })
+}
+
+impl From<&PartTiming> for JsonValue {
+    fn from(value: &PartTiming) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("duration".into(), JsonValue::String(value.duration.clone()));
+        map.insert("nanos".into(), JsonValue::Number(value.nanos));
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "samples".into(),
+            value
+                .samples
+                .map_or(JsonValue::Null, |samples| JsonValue::Number(samples as f64)),
+        );
+        #[allow(clippy::cast_precision_loss)]
+        let optional = [
+            ("median", value.median_nanos),
+            ("spread", value.spread_nanos),
+            ("allocations", value.allocations.map(|a| a as f64)),
+            ("bytes", value.bytes.map(|b| b as f64)),
+            ("peak_bytes", value.peak_bytes.map(|b| b as f64)),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                map.insert(key.into(), JsonValue::Number(value));
+            }
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for PartTiming {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected part timing to be a JSON object.")?;
+
+        let duration = json
+            .get("duration")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected part.duration to be a string.")?;
+
+        let nanos = json
+            .get("nanos")
+            .and_then(|v| v.get::<f64>().copied())
+            .ok_or("Expected part.nanos to be a number.")?;
+
+        let samples = json
+            .get("samples")
+            .map(|v| if v.is_null() { None } else { v.get::<f64>() })
+            .ok_or("Expected part.samples to be null or a number.")?;
+
+        // median, spread and allocation figures are optional, timings stored before they were measured lack them.
+        let optional = |key: &str| -> Result<Option<f64>, String> {
+            json.get(key)
+                .map(|v| {
+                    v.get::<f64>()
+                        .copied()
+                        .ok_or(format!("Expected part.{key} to be a number."))
+                })
+                .transpose()
+        };
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let count = |key: &str| -> Result<Option<u64>, String> {
+            Ok(optional(key)?.map(|count| count as u64))
+        };
+
+        Ok(PartTiming {
+            duration: duration.clone(),
+            nanos,
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            samples: samples.map(|samples| *samples as u64),
+            median_nanos: optional("median")?,
+            spread_nanos: optional("spread")?,
+            allocations: count("allocations")?,
+            bytes: count("bytes")?,
+            peak_bytes: count("peak_bytes")?,
+        })
+    }
+}
+
+impl From<&Timing> for JsonValue {
+    fn from(value: &Timing) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("total_nanos".into(), JsonValue::Number(value.total_nanos));
+
+        if let Some(parse) = &value.parse {
+            map.insert("parse".into(), JsonValue::from(parse));
+        }
+
+        if let Some(timed_out_nanos) = value.timed_out_nanos {
+            map.insert("timed_out_nanos".into(), JsonValue::Number(timed_out_nanos));
+        }
+
+        map.insert(
+            "part_1".into(),
+            value
+                .part_1
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
+
+        map.insert(
+            "part_2".into(),
+            value
+                .part_2
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Timing {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = object(value)?;
+
+        let part = |name: &str| -> Result<Option<PartTiming>, String> {
+            match json.get(name) {
+                None => Err(format!("Expected timing.{name} to be null or an object.")),
+                Some(v) if v.is_null() => Ok(None),
+                Some(v) => PartTiming::try_from(v).map(Some),
+            }
+        };
+
+        Ok(Timing {
+            day: parse_day(json)?,
+            // optional, only days with a `parse` function have a parse time.
+            parse: match json.get("parse") {
+                None => None,
+                Some(v) if v.is_null() => None,
+                Some(v) => Some(PartTiming::try_from(v)?),
+            },
+            part_1: part("part_1")?,
+            part_2: part("part_2")?,
+            total_nanos: parse_total_nanos(json)?,
+            // optional, only days that ran out of time have one.
+            timed_out_nanos: json
+                .get("timed_out_nanos")
+                .map(|v| {
+                    v.get::<f64>()
+                        .copied()
+                        .ok_or("Expected timing.timed_out_nanos to be a number.")
+                })
+                .transpose()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::JsonFormat;
+    use crate::day;
+    use crate::template::alloc::AllocStats;
+    use crate::template::timings::{PartTiming, Timing, Timings, TimingsFormat};
+
+    #[test]
+    fn migrates_version_1() {
+        let json = r#"{ "data": [{ "day": "03", "part_1": "1.5µs", "part_2": null, "total_nanos": 1500 }] }"#;
+        let timings = JsonFormat.deserialize(json).unwrap();
+        assert_eq!(
+            timings.data[0].part_1,
+            Some(PartTiming {
+                duration: "1.5µs".into(),
+                nanos: 1500.0,
+                samples: None,
+                median_nanos: None,
+                spread_nanos: None,
+                allocations: None,
+                bytes: None,
+                peak_bytes: None,
+            })
+        );
+    }
+
+    #[test]
+    fn round_trips_version_2() {
+        let timings = Timings {
+            data: vec![Timing {
+                day: day!(9),
+                parse: None,
+                part_1: PartTiming::from_duration("2.1ms", Some(476)).map(|part| {
+                    part.with_median("2.0ms", "50.0µs")
+                        .with_allocations(AllocStats {
+                            allocations: 12,
+                            bytes: 4096,
+                            peak_bytes: 1024,
+                        })
+                }),
+                part_2: None,
+                total_nanos: 2_100_000.0,
+                timed_out_nanos: Some(10e9),
+            }],
+        };
+        let serialized = JsonFormat.serialize(&timings);
+        assert!(serialized.contains(r#""version": 2"#));
+
+        let parsed = JsonFormat.deserialize(&serialized).unwrap();
+        assert_eq!(parsed.data[0].day, day!(9));
+        assert_eq!(parsed.data[0].part_1, timings.data[0].part_1);
+        assert_eq!(parsed.data[0].part_2, None);
+        assert_eq!(parsed.data[0].timed_out_nanos, Some(10e9));
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let json = r#"{ "version": 3, "data": [] }"#;
+        assert!(JsonFormat.deserialize(json).is_err());
+    }
+}
@@ -0,0 +1,612 @@
+//! Stored benchmark timings, in one of several file formats chosen by the file extension.
+
+use std::{fs, io::ErrorKind, path::Path, str::FromStr};
+
+use crate::template::alloc::AllocStats;
+use crate::template::Day;
+
+mod csv;
+mod json;
+mod toml;
+
+pub static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+
+/// Version of the stored schema.
+/// Version 1 only stored the printed duration of each part, version 2 adds nanos and samples.
+/// The median, spread and allocation figures of a part are optional within version 2.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Benchmark time of a single part.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartTiming {
+    /// The duration as printed by the runner, e.g. `74.13µs`.
+    pub duration: String,
+    pub nanos: f64,
+    /// Number of benchmark samples, unknown for timings migrated from version 1.
+    pub samples: Option<u64>,
+    /// Median of the benchmark samples, unknown for timings stored before it was measured.
+    pub median_nanos: Option<f64>,
+    /// Median absolute deviation of the benchmark samples.
+    pub spread_nanos: Option<f64>,
+    /// Number of heap allocations, only known if counted with `cargo time --dhat` or `--allocs`.
+    pub allocations: Option<u64>,
+    /// Total bytes allocated, only known if counted with `cargo time --allocs`.
+    pub bytes: Option<u64>,
+    /// Most bytes live at once, only known if counted with `cargo time --allocs`.
+    pub peak_bytes: Option<u64>,
+}
+
+impl PartTiming {
+    /// Creates a part timing from a duration as printed by the runner.
+    pub fn from_duration(duration: &str, samples: Option<u64>) -> Option<Self> {
+        Some(PartTiming {
+            duration: duration.to_string(),
+            nanos: parse_duration(duration)?,
+            samples,
+            median_nanos: None,
+            spread_nanos: None,
+            allocations: None,
+            bytes: None,
+            peak_bytes: None,
+        })
+    }
+
+    /// Adds the median and spread as printed by the runner, e.g. `70.2ns` and `3.1ns`.
+    #[must_use]
+    pub fn with_median(self, median: &str, spread: &str) -> Self {
+        PartTiming {
+            median_nanos: parse_duration(median),
+            spread_nanos: parse_duration(spread),
+            ..self
+        }
+    }
+
+    /// Adds the allocations as printed by the runner with the `count-allocations` feature.
+    #[must_use]
+    pub fn with_allocations(self, stats: AllocStats) -> Self {
+        PartTiming {
+            allocations: Some(stats.allocations),
+            bytes: Some(stats.bytes),
+            peak_bytes: Some(stats.peak_bytes),
+            ..self
+        }
+    }
+
+    /// The median if known, otherwise the mean.
+    /// Used to compare runs, as the median is less affected by outliers.
+    pub fn median_or_mean(&self) -> f64 {
+        self.median_nanos.unwrap_or(self.nanos)
+    }
+}
+
+/// Parses a duration as formatted by `Duration`'s `Debug` impl into nanoseconds.
+pub fn parse_duration(duration: &str) -> Option<f64> {
+    // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
+    let parse_to_float = |postfix: &str| duration.split(postfix).next()?.trim().parse::<f64>().ok();
+
+    match duration {
+        s if s.contains("ns") => parse_to_float("ns"),
+        s if s.contains("µs") => parse_to_float("µs").map(|x| x * 1000_f64),
+        s if s.contains("ms") => parse_to_float("ms").map(|x| x * 1_000_000_f64),
+        _ => parse_to_float("s").map(|x| x * 1_000_000_000_f64),
+    }
+}
+
+/// Represents benchmark times for a single day.
+#[derive(Clone, Debug)]
+pub struct Timing {
+    pub day: Day,
+    /// Time of the day's `parse` function, if it declares one.
+    pub parse: Option<PartTiming>,
+    pub part_1: Option<PartTiming>,
+    pub part_2: Option<PartTiming>,
+    pub total_nanos: f64,
+    /// The budget the day ran out of, if it was stopped before all its parts finished.
+    /// Parts without a timing did not finish in time.
+    pub timed_out_nanos: Option<f64>,
+}
+
+impl Timing {
+    /// Attaches allocation counts to the parts that were run, by their label (`Parse`, `Part 1` or `Part 2`).
+    pub fn set_allocations(&mut self, allocations: &[(String, u64)]) {
+        for (label, allocations) in allocations {
+            let part = match label.as_str() {
+                "Parse" => &mut self.parse,
+                "Part 1" => &mut self.part_1,
+                "Part 2" => &mut self.part_2,
+                _ => continue,
+            };
+            if let Some(part) = part {
+                part.allocations = Some(*allocations);
+            }
+        }
+    }
+}
+
+/// Represents benchmark times for a set of days.
+/// Can be serialized from / to JSON, CSV and TOML.
+#[derive(Clone, Debug, Default)]
+pub struct Timings {
+    pub data: Vec<Timing>,
+}
+
+/// A file format timings can be stored in.
+pub trait TimingsFormat {
+    fn serialize(&self, timings: &Timings) -> String;
+
+    /// Parses stored timings, migrating older schema versions.
+    fn deserialize(&self, contents: &str) -> Result<Timings, String>;
+}
+
+/// The format of a timings file, based on its extension.
+pub fn format_for(path: &Path) -> Result<&'static dyn TimingsFormat, String> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => Ok(&json::JsonFormat),
+        Some("csv") => Ok(&csv::CsvFormat),
+        Some("toml") => Ok(&toml::TomlFormat),
+        _ => Err(format!(
+            "unsupported timings file \"{}\", expected a .json, .csv or .toml extension.",
+            path.display()
+        )),
+    }
+}
+
+impl Timings {
+    /// Dehydrate timings to a file, in the format matching its extension.
+    pub fn store_file(&self, path: &Path) -> Result<(), String> {
+        let format = format_for(path)?;
+        fs::write(path, format.serialize(self))
+            .map_err(|e| format!("could not write timings to \"{}\": {e}", path.display()))
+    }
+
+    /// Rehydrate timings from a file. If not present, returns empty timings.
+    pub fn read_from_file(path: &Path) -> Result<Self, String> {
+        let format = format_for(path)?;
+        match fs::read_to_string(path) {
+            Ok(contents) => format.deserialize(&contents).map_err(|e| {
+                format!(
+                    "could not read timings from \"{}\": {e} Fix or remove the file to store new timings.",
+                    path.display()
+                )
+            }),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Timings::default()),
+            Err(e) => Err(format!(
+                "could not read timings from \"{}\": {e}",
+                path.display()
+            )),
+        }
+    }
+
+    /// Merge two sets of timings, overwriting `self` with `other` if present.
+    pub fn merge(&self, new: &Self) -> Self {
+        let mut data: Vec<Timing> = vec![];
+
+        for timing in &new.data {
+            data.push(timing.clone());
+        }
+
+        for timing in &self.data {
+            if !data.iter().any(|t| t.day == timing.day) {
+                data.push(timing.clone());
+            }
+        }
+
+        data.sort_unstable_by_key(|a| a.day);
+        Timings { data }
+    }
+
+    /// Sum up total duration of timings as millis.
+    pub fn total_millis(&self) -> f64 {
+        self.data.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64
+    }
+
+    /// Whether all parts of a day were timed. Days that timed out are never complete.
+    pub fn is_day_complete(&self, day: Day) -> bool {
+        self.data.iter().any(|t| {
+            t.day == day && t.part_1.is_some() && t.part_2.is_some() && t.timed_out_nanos.is_none()
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Columns of the flat representation used by the CSV and TOML formats.
+const FIELDS: [&str; 27] = [
+    "day",
+    "parse",
+    "parse_nanos",
+    "parse_samples",
+    "parse_median_nanos",
+    "parse_spread_nanos",
+    "parse_allocations",
+    "parse_bytes",
+    "parse_peak_bytes",
+    "part_1",
+    "part_1_nanos",
+    "part_1_samples",
+    "part_1_median_nanos",
+    "part_1_spread_nanos",
+    "part_1_allocations",
+    "part_1_bytes",
+    "part_1_peak_bytes",
+    "part_2",
+    "part_2_nanos",
+    "part_2_samples",
+    "part_2_median_nanos",
+    "part_2_spread_nanos",
+    "part_2_allocations",
+    "part_2_bytes",
+    "part_2_peak_bytes",
+    "total_nanos",
+    "timed_out_nanos",
+];
+
+/// Columns that may be missing from files written before they were introduced.
+const OPTIONAL_FIELDS: [&str; 19] = [
+    "parse",
+    "parse_nanos",
+    "parse_samples",
+    "parse_median_nanos",
+    "parse_spread_nanos",
+    "parse_allocations",
+    "parse_bytes",
+    "parse_peak_bytes",
+    "part_1_median_nanos",
+    "part_1_spread_nanos",
+    "part_1_allocations",
+    "part_1_bytes",
+    "part_1_peak_bytes",
+    "part_2_median_nanos",
+    "part_2_spread_nanos",
+    "part_2_allocations",
+    "part_2_bytes",
+    "part_2_peak_bytes",
+    "timed_out_nanos",
+];
+
+/// A value in the flat representation.
+enum Field {
+    Text(String),
+    Number(String),
+}
+
+impl Timing {
+    /// The values for [`FIELDS`], `None` for missing parts.
+    fn to_fields(&self) -> Vec<Option<Field>> {
+        let number = |value: Option<f64>| value.map(|value| Field::Number(value.to_string()));
+        let count = |value: Option<u64>| value.map(|value| Field::Number(value.to_string()));
+        let part = |part: &Option<PartTiming>| match part {
+            Some(part) => [
+                Some(Field::Text(part.duration.clone())),
+                Some(Field::Number(part.nanos.to_string())),
+                count(part.samples),
+                number(part.median_nanos),
+                number(part.spread_nanos),
+                count(part.allocations),
+                count(part.bytes),
+                count(part.peak_bytes),
+            ],
+            None => [None, None, None, None, None, None, None, None],
+        };
+
+        let mut fields = vec![Some(Field::Text(self.day.to_string()))];
+        fields.extend(part(&self.parse));
+        fields.extend(part(&self.part_1));
+        fields.extend(part(&self.part_2));
+        fields.push(Some(Field::Number(self.total_nanos.to_string())));
+        fields.push(number(self.timed_out_nanos));
+        fields
+    }
+
+    /// Reads a timing from the flat representation, `get` returns the raw value of a field.
+    fn from_fields<'a>(get: impl Fn(&str) -> Option<&'a str>) -> Result<Self, String> {
+        let number = |name: &str| -> Result<Option<f64>, String> {
+            get(name)
+                .map(|value| {
+                    value
+                        .parse::<f64>()
+                        .map_err(|_| format!("expected `{name}` to be a number, found '{value}'."))
+                })
+                .transpose()
+        };
+
+        let part = |name: &str| -> Result<Option<PartTiming>, String> {
+            let Some(duration) = get(name) else {
+                return Ok(None);
+            };
+            let nanos = number(&format!("{name}_nanos"))?
+                .ok_or_else(|| format!("expected `{name}_nanos` for `{name}`."))?;
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let count = |field: &str| -> Result<Option<u64>, String> {
+                Ok(number(&format!("{name}_{field}"))?.map(|count| count as u64))
+            };
+            Ok(Some(PartTiming {
+                duration: duration.to_string(),
+                nanos,
+                samples: count("samples")?,
+                median_nanos: number(&format!("{name}_median_nanos"))?,
+                spread_nanos: number(&format!("{name}_spread_nanos"))?,
+                allocations: count("allocations")?,
+                bytes: count("bytes")?,
+                peak_bytes: count("peak_bytes")?,
+            }))
+        };
+
+        Ok(Timing {
+            day: get("day")
+                .and_then(|day| Day::from_str(day).ok())
+                .ok_or("expected `day` to be a day between 01 and 25.")?,
+            parse: part("parse")?,
+            part_1: part("part_1")?,
+            part_2: part("part_2")?,
+            total_nanos: number("total_nanos")?.ok_or("expected `total_nanos`.")?,
+            timed_out_nanos: number("timed_out_nanos")?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use crate::day;
+
+    use super::{PartTiming, Timing, Timings};
+
+    fn get_mock_timings() -> Timings {
+        Timings {
+            data: vec![
+                Timing {
+                    day: day!(1),
+                    parse: None,
+                    part_1: PartTiming::from_duration("10ms", None),
+                    part_2: PartTiming::from_duration("20ms", None),
+                    total_nanos: 3e+10,
+                    timed_out_nanos: None,
+                },
+                Timing {
+                    day: day!(2),
+                    parse: None,
+                    part_1: PartTiming::from_duration("30ms", None),
+                    part_2: PartTiming::from_duration("40ms", None),
+                    total_nanos: 7e+10,
+                    timed_out_nanos: None,
+                },
+                Timing {
+                    day: day!(4),
+                    parse: None,
+                    part_1: PartTiming::from_duration("40ms", None),
+                    part_2: None,
+                    total_nanos: 4e+10,
+                    timed_out_nanos: None,
+                },
+            ],
+        }
+    }
+
+    mod deserialization {
+        use crate::{day, template::timings::Timings};
+
+        #[test]
+        fn handles_json_timings() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            assert_eq!(timings.data.len(), 1);
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.day, day!(1));
+            assert_eq!(
+                timing.part_1.as_ref().map(|part| part.duration.as_str()),
+                Some("1ms")
+            );
+            assert_eq!(timing.part_1.as_ref().map(|part| part.nanos), Some(1e6));
+            assert_eq!(timing.part_2, None);
+            assert_eq!(timing.total_nanos, 1_000_000_000_f64);
+        }
+
+        #[test]
+        fn handles_empty_timings() {
+            let json = r#"{ "data": [] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            assert_eq!(timings.data.len(), 0);
+        }
+
+        #[test]
+        #[should_panic]
+        fn panics_for_invalid_json() {
+            let json = r#"{}"#.to_string();
+            Timings::try_from(json).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn panics_for_malformed_timings() {
+            let json = r#"{ "data": [{ "day": "01" }, { "day": "26" }, { "day": "02", "part_2": null, "total_nanos": 0 }] }"#.to_string();
+            Timings::try_from(json).unwrap();
+        }
+    }
+
+    mod serialization {
+        use super::get_mock_timings;
+        use std::collections::HashMap;
+        use tinyjson::JsonValue;
+
+        #[test]
+        fn serializes_timings() {
+            let timings = get_mock_timings();
+            let value = JsonValue::try_from(timings).unwrap();
+            assert_eq!(
+                value
+                    .get::<HashMap<String, JsonValue>>()
+                    .unwrap()
+                    .get("data")
+                    .unwrap()
+                    .get::<Vec<JsonValue>>()
+                    .unwrap()
+                    .len(),
+                3
+            );
+        }
+    }
+
+    mod is_day_complete {
+        use crate::{
+            day,
+            template::timings::{PartTiming, Timing, Timings},
+        };
+
+        #[test]
+        fn handles_completed_days() {
+            let timings = Timings {
+                data: vec![Timing {
+                    day: day!(1),
+                    parse: None,
+                    part_1: PartTiming::from_duration("1ms", None),
+                    part_2: PartTiming::from_duration("2ms", None),
+                    total_nanos: 3_000_000_000_f64,
+                    timed_out_nanos: None,
+                }],
+            };
+
+            assert_eq!(timings.is_day_complete(day!(1)), true);
+        }
+
+        #[test]
+        fn handles_partial_days() {
+            let timings = Timings {
+                data: vec![Timing {
+                    day: day!(1),
+                    parse: None,
+                    part_1: PartTiming::from_duration("1ms", None),
+                    part_2: None,
+                    total_nanos: 1_000_000_000_f64,
+                    timed_out_nanos: None,
+                }],
+            };
+
+            assert_eq!(timings.is_day_complete(day!(1)), false);
+        }
+
+        #[test]
+        fn handles_uncompleted_days() {
+            let timings = Timings {
+                data: vec![Timing {
+                    day: day!(1),
+                    parse: None,
+                    part_1: None,
+                    part_2: None,
+                    total_nanos: 0.0,
+                    timed_out_nanos: None,
+                }],
+            };
+
+            assert_eq!(timings.is_day_complete(day!(1)), false);
+        }
+    }
+
+    mod merge {
+        use crate::{
+            day,
+            template::timings::{PartTiming, Timing, Timings},
+        };
+
+        use super::get_mock_timings;
+
+        #[test]
+        fn handles_disjunct_timings() {
+            let timings = get_mock_timings();
+            let other = Timings {
+                data: vec![Timing {
+                    day: day!(3),
+                    parse: None,
+                    part_1: None,
+                    part_2: None,
+                    total_nanos: 0_f64,
+                    timed_out_nanos: None,
+                }],
+            };
+            let merged = timings.merge(&other);
+            assert_eq!(merged.data.len(), 4);
+            assert_eq!(merged.data[0].day, day!(1));
+            assert_eq!(merged.data[1].day, day!(2));
+            assert_eq!(merged.data[2].day, day!(3));
+            assert_eq!(merged.data[3].day, day!(4));
+        }
+
+        #[test]
+        fn handles_overlapping_timings() {
+            let timings = get_mock_timings();
+
+            let other = Timings {
+                data: vec![Timing {
+                    day: day!(2),
+                    parse: None,
+                    part_1: None,
+                    part_2: None,
+                    total_nanos: 0_f64,
+                    timed_out_nanos: None,
+                }],
+            };
+            let merged = timings.merge(&other);
+
+            assert_eq!(merged.data.len(), 3);
+            assert_eq!(merged.data[0].day, day!(1));
+            assert_eq!(merged.data[1].day, day!(2));
+            assert_eq!(merged.data[1].total_nanos, 0_f64);
+            assert_eq!(merged.data[2].day, day!(4));
+        }
+
+        #[test]
+        fn handles_empty_timings() {
+            let timings = Timings::default();
+            let other = get_mock_timings();
+            let merged = timings.merge(&other);
+            assert_eq!(merged.data.len(), 3);
+        }
+
+        #[test]
+        fn handles_empty_other_timings() {
+            let timings = get_mock_timings();
+            let other = Timings::default();
+            let merged = timings.merge(&other);
+            assert_eq!(merged.data.len(), 3);
+        }
+    }
+
+    mod storage {
+        use std::{env, fs, path::PathBuf};
+
+        use super::get_mock_timings;
+        use crate::template::timings::Timings;
+
+        fn temp_path(name: &str) -> PathBuf {
+            env::temp_dir().join(format!("aoc-timings-{}-{name}", std::process::id()))
+        }
+
+        #[test]
+        fn stores_and_reads_every_format() {
+            for extension in ["json", "csv", "toml"] {
+                let path = temp_path(&format!("store.{extension}"));
+                get_mock_timings().store_file(&path).unwrap();
+                let timings = Timings::read_from_file(&path).unwrap();
+                fs::remove_file(&path).unwrap();
+
+                assert_eq!(timings.data.len(), 3, "{extension}");
+                assert_eq!(timings.data[2].part_1, get_mock_timings().data[2].part_1);
+            }
+        }
+
+        #[test]
+        fn reports_missing_and_corrupt_files() {
+            let missing = Timings::read_from_file(&temp_path("missing.json")).unwrap();
+            assert!(missing.data.is_empty());
+
+            let path = temp_path("corrupt.json");
+            fs::write(&path, "{ \"data\": [").unwrap();
+            let error = Timings::read_from_file(&path).unwrap_err();
+            fs::remove_file(&path).unwrap();
+            assert!(error.contains("not valid JSON file."), "{error}");
+
+            assert!(Timings::read_from_file(&temp_path("timings.yaml")).is_err());
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! TOML storage, with a top-level `version` and one `[[data]]` table per day.
+//! Only the subset of TOML written by [`TomlFormat::serialize`] is supported.
+
+use std::collections::HashMap;
+
+use super::{Field, Timing, Timings, TimingsFormat, FIELDS, SCHEMA_VERSION};
+
+pub struct TomlFormat;
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Parses a basic string or a number.
+fn parse_value(value: &str) -> Result<String, String> {
+    let Some(inner) = value.strip_prefix('"') else {
+        return value
+            .parse::<f64>()
+            .map(|_| value.to_string())
+            .map_err(|_| format!("expected a string or number, found `{value}`."));
+    };
+
+    let mut result = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped @ ('\\' | '"')) => result.push(escaped),
+                _ => return Err(format!("unsupported escape sequence in `{value}`.")),
+            },
+            '"' if chars.as_str().trim().is_empty() => return Ok(result),
+            '"' => return Err(format!("unexpected characters after `{value}`.")),
+            c => result.push(c),
+        }
+    }
+    Err(format!("unterminated string `{value}`."))
+}
+
+impl TimingsFormat for TomlFormat {
+    fn serialize(&self, timings: &Timings) -> String {
+        let mut lines = vec![format!("version = {SCHEMA_VERSION}")];
+
+        for timing in &timings.data {
+            lines.push(String::new());
+            lines.push("[[data]]".into());
+            for (name, field) in FIELDS.iter().zip(timing.to_fields()) {
+                match field {
+                    Some(Field::Text(value)) => lines.push(format!("{name} = {}", quote(&value))),
+                    Some(Field::Number(value)) => lines.push(format!("{name} = {value}")),
+                    None => {}
+                }
+            }
+        }
+
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    fn deserialize(&self, contents: &str) -> Result<Timings, String> {
+        let mut version = None;
+        let mut tables: Vec<(usize, HashMap<String, String>)> = vec![];
+
+        for (index, line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line == "[[data]]" {
+                tables.push((line_number, HashMap::new()));
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {line_number}: expected `key = value`."))?;
+            let (key, value) = (key.trim(), value.trim());
+            let value = parse_value(value).map_err(|e| format!("line {line_number}: {e}"))?;
+
+            match tables.last_mut() {
+                Some((_, table)) => {
+                    table.insert(key.to_string(), value);
+                }
+                None if key == "version" => version = Some(value),
+                None => return Err(format!("line {line_number}: unexpected key `{key}`.")),
+            }
+        }
+
+        match version.as_deref() {
+            None => return Err("expected a top-level `version`.".into()),
+            Some(version) if version != SCHEMA_VERSION.to_string() => {
+                return Err(format!(
+                    "unsupported schema version {version}, expected {SCHEMA_VERSION}."
+                ));
+            }
+            Some(_) => {}
+        }
+
+        let data = tables
+            .iter()
+            .map(|(line_number, table)| {
+                Timing::from_fields(|name| table.get(name).map(String::as_str))
+                    .map_err(|e| format!("table at line {line_number}: {e}"))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Timings { data })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::TomlFormat;
+    use crate::day;
+    use crate::template::timings::{PartTiming, Timing, Timings, TimingsFormat};
+
+    #[test]
+    fn round_trips_timings() {
+        let timings = Timings {
+            data: vec![Timing {
+                day: day!(11),
+                parse: None,
+                part_1: PartTiming::from_duration("180.3µs", Some(5546)),
+                part_2: None,
+                total_nanos: 180_300.0,
+                timed_out_nanos: None,
+            }],
+        };
+
+        let toml = TomlFormat.serialize(&timings);
+        assert_eq!(
+            toml,
+            [
+                "version = 2",
+                "",
+                "[[data]]",
+                "day = \"11\"",
+                "part_1 = \"180.3µs\"",
+                "part_1_nanos = 180300",
+                "part_1_samples = 5546",
+                "total_nanos = 180300",
+                "",
+            ]
+            .join("\n")
+        );
+
+        let parsed = TomlFormat.deserialize(&toml).unwrap();
+        assert_eq!(parsed.data[0].day, day!(11));
+        assert_eq!(parsed.data[0].part_1, timings.data[0].part_1);
+        assert_eq!(parsed.data[0].part_2, None);
+    }
+
+    #[test]
+    fn reports_corrupt_files() {
+        assert_eq!(
+            TomlFormat
+                .deserialize("[[data]]\nday = \"01\"")
+                .unwrap_err(),
+            "expected a top-level `version`."
+        );
+        assert_eq!(
+            TomlFormat
+                .deserialize("version = 2\n[[data]]\nday = \"01")
+                .unwrap_err(),
+            "line 3: unterminated string `\"01`."
+        );
+        assert_eq!(
+            TomlFormat
+                .deserialize("version = 2\n[[data]]\nday = \"01\"")
+                .unwrap_err(),
+            "table at line 2: expected `total_nanos`."
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! Checks the structure of a puzzle input before it is solved.
+//!
+//! Every input is linted for line endings, a trailing newline and trailing whitespace. A day can
+//! add its own checks by passing `validate = <fn>` to [`solution!`](crate::solution), with
+//! `fn validate(input: &mut Validator)` reporting problems at the span they were found at.
+
+use std::fmt::{Display, Write};
+use std::ops::RangeInclusive;
+
+use crate::parse::{ParseError, ParseResult, Span};
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The input can not be solved as is.
+    Error,
+    /// The input is solvable, but unusual in a way that can trip up a solution.
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem with the input, pointing at a 1-based line and column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: line {}, column {}: {}",
+            self.severity, self.line, self.column, self.message
+        )
+    }
+}
+
+impl From<ParseError> for Diagnostic {
+    fn from(value: ParseError) -> Self {
+        Self {
+            severity: Severity::Error,
+            line: value.line,
+            column: value.column,
+            message: value.message,
+        }
+    }
+}
+
+impl Diagnostic {
+    /// The diagnostic followed by the line of `input` it points at, with a marker below the column.
+    #[must_use]
+    pub fn render(&self, input: &str) -> String {
+        let mut rendered = format!(
+            "{ANSI_BOLD}{}{ANSI_RESET}: line {}, column {}: {}",
+            self.severity, self.line, self.column, self.message
+        );
+        if let Some(line) = input.split('\n').nth(self.line - 1) {
+            let line = line.trim_end_matches('\r');
+            let gutter = " ".repeat(self.line.to_string().len());
+            let _ = write!(
+                rendered,
+                "\n {} | {line}\n {gutter} | {}^",
+                self.line,
+                " ".repeat(self.column - 1)
+            );
+        }
+        rendered
+    }
+}
+
+/// Collects the diagnostics of an input, see the [module documentation](self).
+pub struct Validator<'a> {
+    input: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Validator<'a> {
+    /// Lints `input`, further checks can be added with the methods of the validator.
+    #[must_use]
+    pub fn new(input: &'a str) -> Self {
+        let mut validator = Self {
+            input,
+            diagnostics: vec![],
+        };
+        validator.lint();
+        validator
+    }
+
+    /// The whole input, to be split up and checked.
+    #[must_use]
+    pub fn input(&self) -> Span<'a> {
+        Span::new(self.input)
+    }
+
+    pub fn error(&mut self, at: Span, message: impl Into<String>) {
+        self.report(Severity::Error, at.line(), at.column(), message);
+    }
+
+    pub fn warning(&mut self, at: Span, message: impl Into<String>) {
+        self.report(Severity::Warning, at.line(), at.column(), message);
+    }
+
+    fn report(
+        &mut self,
+        severity: Severity,
+        line: usize,
+        column: usize,
+        message: impl Into<String>,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            line,
+            column,
+            message: message.into(),
+        });
+    }
+
+    /// Reports the error of a failed parse, returning the parsed value otherwise.
+    pub fn check<T>(&mut self, result: ParseResult<T>) -> Option<T> {
+        result.map_err(|e| self.diagnostics.push(e.into())).ok()
+    }
+
+    /// Parses `span` as an unsigned integer, reporting it if it isn't one or lies outside `range`.
+    pub fn unsigned_in<T: std::str::FromStr + PartialOrd + Display>(
+        &mut self,
+        span: Span,
+        range: RangeInclusive<T>,
+    ) -> Option<T> {
+        let value = self.check(span.unsigned::<T>())?;
+        if !range.contains(&value) {
+            self.error(
+                span.trim(),
+                format!(
+                    "expected a value in {}..={}, found {value}",
+                    range.start(),
+                    range.end()
+                ),
+            );
+            return None;
+        }
+        Some(value)
+    }
+
+    /// Checks that `span` is a rectangular grid of the `allowed` characters, reporting every
+    /// offending character and row.
+    pub fn grid(&mut self, span: Span, allowed: &str) {
+        let mut width = None;
+        for line in span.lines() {
+            // counting the position of a row takes a scan of the input, so only rows with errors do.
+            let mut start = None;
+            for (column, c) in line.as_str().chars().enumerate() {
+                if !allowed.contains(c) {
+                    let (row, first) = *start.get_or_insert_with(|| (line.line(), line.column()));
+                    self.report(
+                        Severity::Error,
+                        row,
+                        first + column,
+                        format!("unexpected character '{c}', expected one of '{allowed}'"),
+                    );
+                }
+            }
+
+            let length = line.as_str().chars().count();
+            match width {
+                None => width = Some(length),
+                Some(width) if width != length => self.error(
+                    line,
+                    format!("expected a row of length {width} like the first one, found {length}"),
+                ),
+                Some(_) => {}
+            }
+        }
+    }
+
+    /// The diagnostics, ordered by their position in the input.
+    #[must_use]
+    pub fn finish(mut self) -> Vec<Diagnostic> {
+        self.diagnostics
+            .sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column, diagnostic.severity));
+        self.diagnostics
+    }
+
+    /// Lints that apply to every input. Each is reported once, at its first occurrence.
+    fn lint(&mut self) {
+        let input = self.input;
+        if input.trim().is_empty() {
+            self.report(Severity::Warning, 1, 1, "input is empty");
+            return;
+        }
+        if input.starts_with('\u{feff}') {
+            self.report(
+                Severity::Warning,
+                1,
+                1,
+                "input starts with a byte order mark",
+            );
+        }
+
+        let lines: Vec<&str> = input.split('\n').collect();
+        let mut lint = |message: &str, matches: &dyn Fn(&str) -> Option<usize>| {
+            let found: Vec<(usize, usize)> = lines
+                .iter()
+                .enumerate()
+                .filter_map(|(index, line)| Some((index + 1, matches(line)?)))
+                .collect();
+            if let Some((line, column)) = found.first() {
+                let message = match found.len() {
+                    1 => message.to_string(),
+                    count => format!("{message} ({count} lines in total)"),
+                };
+                self.report(Severity::Warning, *line, *column, message);
+            }
+        };
+
+        lint("line ends with \\r\\n", &|line| {
+            line.ends_with('\r').then(|| line.chars().count())
+        });
+        lint("trailing whitespace", &|line| {
+            let line = line.trim_end_matches('\r');
+            let trimmed = line.trim_end();
+            (trimmed.len() < line.len()).then(|| trimmed.chars().count() + 1)
+        });
+
+        let last_line = lines.len();
+        if !input.ends_with('\n') {
+            let column = lines[last_line - 1].chars().count() + 1;
+            self.report(
+                Severity::Warning,
+                last_line,
+                column,
+                "missing trailing newline",
+            );
+        } else if let Some(content) = lines.iter().rposition(|line| !line.trim().is_empty()) {
+            // the last element of `lines` is the empty string after the final newline.
+            if content + 2 < last_line {
+                self.report(
+                    Severity::Warning,
+                    content + 2,
+                    1,
+                    "blank lines at the end of the input",
+                );
+            }
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Diagnostic, Severity, Validator};
+    use crate::parse::Span;
+
+    fn messages(diagnostics: &[Diagnostic]) -> Vec<String> {
+        diagnostics.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn accepts_clean_input() {
+        assert_eq!(Validator::new("1 2\n3 4\n").finish(), []);
+    }
+
+    #[test]
+    fn lints_every_input() {
+        assert_eq!(
+            messages(&Validator::new("1 2\r\n3 4 \r\n5 6").finish()),
+            [
+                "warning: line 1, column 4: line ends with \\r\\n (2 lines in total)",
+                "warning: line 2, column 4: trailing whitespace",
+                "warning: line 3, column 4: missing trailing newline",
+            ]
+        );
+        assert_eq!(
+            messages(&Validator::new("1 2\n\n\n").finish()),
+            ["warning: line 2, column 1: blank lines at the end of the input"]
+        );
+        assert_eq!(
+            messages(&Validator::new("\n").finish()),
+            ["warning: line 1, column 1: input is empty"]
+        );
+    }
+
+    #[test]
+    fn reports_parse_errors_and_ranges() {
+        let mut validator = Validator::new("12,x\n5,120\n");
+        for line in validator.input().lines() {
+            for value in line.split(',') {
+                validator.unsigned_in(value, 1..=99u32);
+            }
+        }
+        assert_eq!(
+            messages(&validator.finish()),
+            [
+                "error: line 1, column 4: expected unsigned integer, found 'x'",
+                "error: line 2, column 3: expected a value in 1..=99, found 120",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_grids() {
+        let mut validator = Validator::new("#.#\n#x\n###\n");
+        validator.grid(validator.input(), "#.");
+        assert_eq!(
+            messages(&validator.finish()),
+            [
+                "error: line 2, column 1: expected a row of length 3 like the first one, found 2",
+                "error: line 2, column 2: unexpected character 'x', expected one of '#.'",
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_the_offending_line() {
+        let mut validator = Validator::new("Register A: 7x\n");
+        let register = validator.input().lines().next().unwrap();
+        validator.check(
+            register
+                .field("Register A", ':')
+                .and_then(Span::unsigned::<u32>),
+        );
+
+        let diagnostics = validator.finish();
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            diagnostics[0].render("Register A: 7x\n"),
+            "\x1b[1merror\x1b[0m: line 1, column 13: expected unsigned integer, found '7x'\n 1 | Register A: 7x\n   |             ^"
+        );
+    }
+}